@@ -0,0 +1,42 @@
+use image::{GrayImage, Luma, Rgba, RgbaImage};
+use imageproc::{distance_transform::euclidean_squared_distance_transform, filter::gaussian_blur_f32};
+
+pub fn get_alpha_mask(buffer: &RgbaImage) -> GrayImage {
+    GrayImage::from_fn(buffer.width(), buffer.height(), |x, y| {
+        Luma([buffer.get_pixel(x, y).0[3]])
+    })
+}
+
+pub fn dilate_mask(mask: &GrayImage, radius: f32) -> GrayImage {
+    let distances = euclidean_squared_distance_transform(mask);
+
+    // Soften the last pixel so the edge isn't jagged
+    GrayImage::from_fn(mask.width(), mask.height(), |x, y| {
+        let distance = distances.get_pixel(x, y).0[0].sqrt() as f32;
+        let coverage = (radius + 0.5 - distance).clamp(0.0, 1.0);
+
+        Luma([(coverage * 255.0).round() as u8])
+    })
+}
+
+pub fn blur_mask(mask: &GrayImage, sigma: f32) -> GrayImage {
+    if sigma <= 0.0 { return mask.clone(); }
+    gaussian_blur_f32(mask, sigma)
+}
+
+pub fn colorize_mask(mask: &GrayImage, color: Rgba<u8>, opacity: f32) -> RgbaImage {
+    RgbaImage::from_fn(mask.width(), mask.height(), |x, y| {
+        let alpha = mask.get_pixel(x, y).0[0] as f32 * (color.0[3] as f32 / 255.0) * opacity;
+        if alpha < 1.0 { return Rgba([0, 0, 0, 0]); }
+
+        Rgba([color.0[0], color.0[1], color.0[2], alpha.round() as u8])
+    })
+}
+
+// Turns a color premultiplied by its alpha back into a normal pixel
+pub fn from_premultiplied(color: [f32; 3], alpha: f32) -> Rgba<u8> {
+    if alpha <= 0.0 { return Rgba([0, 0, 0, 0]); }
+
+    let channel = |value: f32| (value / alpha).round().clamp(0.0, 255.0) as u8;
+    Rgba([channel(color[0]), channel(color[1]), channel(color[2]), (alpha.min(1.0) * 255.0).round() as u8])
+}
@@ -0,0 +1,94 @@
+use image::{Frame, Rgba};
+
+use crate::{color::parse_color, command::Command};
+
+const CHECKER_LIGHT: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const CHECKER_DARK: Rgba<u8> = Rgba([204, 204, 204, 255]);
+
+#[derive(Copy, Clone)]
+pub enum Backdrop {
+    // No colors were given, so the emote is left alone
+    None,
+    Solid(Rgba<u8>),
+    // Start and end color, horizontal or vertical
+    Gradient(Rgba<u8>, Rgba<u8>, bool),
+    // Two colors and the size of a square in pixels
+    Checkerboard(Rgba<u8>, Rgba<u8>, u32),
+}
+
+impl From<&Command> for Backdrop {
+    fn from(command: &Command) -> Self {
+        let colors: Vec<Rgba<u8>> = command.options
+            .iter()
+            .filter_map(|option| parse_color(option))
+            .collect();
+
+        if command.has_option("checker") {
+            let size = command.options
+                .iter()
+                .find_map(|option| option.parse::<f32>().ok())
+                .map_or(8, |size| size.clamp(1.0, 64.0) as u32);
+
+            return match colors[..] {
+                [first, second, ..] => Backdrop::Checkerboard(first, second, size),
+                _ => Backdrop::Checkerboard(CHECKER_LIGHT, CHECKER_DARK, size),
+            };
+        }
+
+        match colors[..] {
+            [first, second, ..] => Backdrop::Gradient(first, second, command.has_option("horizontal")),
+            [color] => Backdrop::Solid(color),
+            [] => Backdrop::None,
+        }
+    }
+}
+
+impl Backdrop {
+    fn color_at(self, x: u32, y: u32, width: u32, height: u32) -> Rgba<u8> {
+        match self {
+            Backdrop::None => Rgba([0, 0, 0, 0]),
+            Backdrop::Solid(color) => color,
+            Backdrop::Gradient(start, end, horizontal) => {
+                let progress = if horizontal {
+                    x as f32 / (width - 1).max(1) as f32
+                } else {
+                    y as f32 / (height - 1).max(1) as f32
+                };
+
+                blend(&end, &start, progress)
+            },
+            Backdrop::Checkerboard(light, dark, size) => {
+                match (x / size + y / size) % 2 {
+                    0 => light,
+                    _ => dark,
+                }
+            },
+        }
+    }
+}
+
+pub fn backdrop(frames: &mut [Frame], backdrop: Backdrop) {
+    if let Backdrop::None = backdrop { return; }
+
+    for frame in frames {
+        let buffer = frame.buffer_mut();
+        let width = buffer.width();
+        let height = buffer.height();
+
+        for (x, y, pixel) in buffer.enumerate_pixels_mut() {
+            let background = backdrop.color_at(x, y, width, height);
+            let opacity = pixel.0[3] as f32 / 255.0;
+
+            *pixel = blend(pixel, &background, opacity);
+        }
+    }
+}
+
+fn blend(foreground: &Rgba<u8>, background: &Rgba<u8>, opacity: f32) -> Rgba<u8> {
+    let channel = |index: usize| {
+        let value = foreground.0[index] as f32 * opacity + background.0[index] as f32 * (1.0 - opacity);
+        value.round() as u8
+    };
+
+    Rgba([channel(0), channel(1), channel(2), 255])
+}
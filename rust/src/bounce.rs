@@ -0,0 +1,72 @@
+use image::{imageops, Frame, RgbaImage};
+
+use crate::{command::Command, easing::get_progress, resize::scale_buffer, utils::{align_gif, align_speed, get_delay_centisecs}};
+
+// Emote is shrunk to leave room above it to jump into
+const BOUNCE_SCALE: f32 = 0.75;
+// Part of the cycle spent touching the ground
+const CONTACT: f32 = 0.1;
+
+#[derive(Copy, Clone)]
+pub struct BounceOptions {
+    // Squash on landing and stretch while moving
+    squash: bool,
+}
+
+impl From<&Command> for BounceOptions {
+    fn from(command: &Command) -> Self {
+        Self { squash: command.has_option("squash") }
+    }
+}
+
+pub fn bounce(frames: &mut Vec<Frame>, speed: f32, options: BounceOptions) {
+    align_speed(frames, 8.0);
+    let Some(frame) = frames.first() else { return };
+    let width = frame.buffer().width() as f32;
+    let height = frame.buffer().height() as f32;
+
+    let delay_centisecs = get_delay_centisecs(frame.delay());
+    let centisecs_per_bounce = (100.0 * speed) / 8.0;
+    let bounce_step = delay_centisecs / centisecs_per_bounce;
+    let interval = (1.0 / bounce_step).floor();
+
+    *frames = align_gif(frames, interval as usize);
+
+    let jump_height = height * (1.0 - BOUNCE_SCALE);
+
+    for (index, frame) in frames.iter_mut().enumerate() {
+        let progress = get_progress(index, bounce_step);
+
+        // Parabola going from the ground (0) to the top (1) and back
+        let altitude = 4.0 * progress * (1.0 - progress);
+        let (scale_x, scale_y) = if options.squash { get_squash(progress) } else { (1.0, 1.0) };
+
+        let bounced_buffer = scale_buffer(
+            frame.buffer(),
+            BOUNCE_SCALE * scale_x,
+            BOUNCE_SCALE * scale_y,
+        );
+
+        // Anchor at the bottom center
+        let x = ((width - bounced_buffer.width() as f32) / 2.0).round() as i64;
+        let y = (height - bounced_buffer.height() as f32 - altitude * jump_height).round() as i64;
+
+        let mut canvas = RgbaImage::new(width as u32, height as u32);
+        imageops::overlay(&mut canvas, &bounced_buffer, x, y);
+
+        *frame.buffer_mut() = canvas;
+    }
+}
+
+fn get_squash(progress: f32) -> (f32, f32) {
+    let time_from_ground = progress.min(1.0 - progress);
+    if time_from_ground < CONTACT / 2.0 {
+        return (1.2, 0.8);
+    }
+
+    // Stretch along the direction of motion, most when moving fastest
+    let velocity = (1.0 - 2.0 * progress).abs();
+    let stretch = 0.1 * velocity;
+
+    (1.0 - stretch, 1.0 + stretch)
+}
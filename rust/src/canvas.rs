@@ -0,0 +1,156 @@
+use image::{imageops, Frame, GenericImageView, Rgba, RgbaImage};
+
+use crate::command::Command;
+
+const TRANSPARENT: Rgba<u8> = Rgba([0, 0, 0, 0]);
+
+#[derive(Copy, Clone)]
+pub enum CropArea {
+    // Width and height in percent, centered
+    Center(f32, f32),
+    // Left, top, width and height in percent
+    Rect(f32, f32, f32, f32),
+}
+
+impl From<&Command> for CropArea {
+    fn from(command: &Command) -> Self {
+        let values: Vec<f32> = command.options
+            .iter()
+            .filter_map(|option| option.parse().ok())
+            .collect();
+
+        if let [x, y, width, height] = values[..] {
+            return CropArea::Rect(x, y, width, height);
+        }
+
+        CropArea::Center(command.param, command.param_extra.unwrap_or(command.param))
+    }
+}
+
+struct Area {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+pub fn crop(frames: &mut [Frame], area: CropArea) {
+    let Some(frame) = frames.first() else { return };
+    let width = frame.buffer().width() as f32;
+    let height = frame.buffer().height() as f32;
+
+    let (x, y, crop_width, crop_height) = match area {
+        CropArea::Center(crop_width, crop_height) => {
+            let crop_width = crop_width.clamp(1.0, 100.0);
+            let crop_height = crop_height.clamp(1.0, 100.0);
+
+            ((100.0 - crop_width) / 2.0, (100.0 - crop_height) / 2.0, crop_width, crop_height)
+        },
+        CropArea::Rect(x, y, crop_width, crop_height) => (
+            x.clamp(0.0, 99.0),
+            y.clamp(0.0, 99.0),
+            crop_width.clamp(1.0, 100.0 - x.clamp(0.0, 99.0)),
+            crop_height.clamp(1.0, 100.0 - y.clamp(0.0, 99.0)),
+        ),
+    };
+
+    let area = Area {
+        x: (width * x / 100.0).round() as u32,
+        y: (height * y / 100.0).round() as u32,
+        width: (width * crop_width / 100.0).round().max(1.0) as u32,
+        height: (height * crop_height / 100.0).round().max(1.0) as u32,
+    };
+
+    crop_frames(frames, area);
+}
+
+pub fn pad(frames: &mut [Frame], amount: f32, color: Option<Rgba<u8>>) {
+    let Some(frame) = frames.first() else { return };
+    let size = frame.buffer().width().max(frame.buffer().height()) as f32;
+
+    // Amount is in percent of the largest side
+    let margin = (size * amount.clamp(0.0, 100.0) / 100.0).round() as u32;
+    pad_frames(frames, margin, margin, margin, margin, color.unwrap_or(TRANSPARENT));
+}
+
+pub fn expand(frames: &mut [Frame], margin: u32) {
+    pad_frames(frames, margin, margin, margin, margin, TRANSPARENT);
+}
+
+pub fn trim(frames: &mut [Frame]) {
+    let Some(frame) = frames.first() else { return };
+    let mut left = frame.buffer().width();
+    let mut top = frame.buffer().height();
+    let mut right = 0;
+    let mut bottom = 0;
+
+    // Take every frame into account so the content doesn't move around
+    for frame in frames.iter() {
+        for (x, y, pixel) in frame.buffer().enumerate_pixels() {
+            if pixel.0[3] == 0 { continue; }
+
+            left = left.min(x);
+            top = top.min(y);
+            right = right.max(x + 1);
+            bottom = bottom.max(y + 1);
+        }
+    }
+
+    if right <= left || bottom <= top { return; }
+
+    crop_frames(frames, Area {
+        x: left,
+        y: top,
+        width: right - left,
+        height: bottom - top,
+    });
+}
+
+pub fn square(frames: &mut [Frame], color: Option<Rgba<u8>>) {
+    let Some(frame) = frames.first() else { return };
+    let width = frame.buffer().width();
+    let height = frame.buffer().height();
+
+    let size = width.max(height);
+    let horizontal = size - width;
+    let vertical = size - height;
+
+    pad_frames(
+        frames,
+        horizontal / 2,
+        vertical / 2,
+        horizontal - horizontal / 2,
+        vertical - vertical / 2,
+        color.unwrap_or(TRANSPARENT),
+    );
+}
+
+fn crop_frames(frames: &mut [Frame], area: Area) {
+    for frame in frames {
+        // Rounding can push the area slightly out of bounds
+        let x = area.x.min(frame.buffer().width() - 1);
+        let y = area.y.min(frame.buffer().height() - 1);
+        let width = area.width.min(frame.buffer().width() - x);
+        let height = area.height.min(frame.buffer().height() - y);
+
+        let cropped_buffer = frame.buffer()
+            .view(x, y, width, height)
+            .to_image();
+
+        *frame.buffer_mut() = cropped_buffer;
+    }
+}
+
+fn pad_frames(frames: &mut [Frame], left: u32, top: u32, right: u32, bottom: u32, fill: Rgba<u8>) {
+    if left == 0 && top == 0 && right == 0 && bottom == 0 { return; }
+
+    for frame in frames {
+        let width = frame.buffer().width() + left + right;
+        let height = frame.buffer().height() + top + bottom;
+
+        let mut padded_buffer = RgbaImage::from_pixel(width, height, fill);
+        imageops::replace(&mut padded_buffer, frame.buffer(), left as i64, top as i64);
+
+        *frame.buffer_mut() = padded_buffer;
+    }
+}
@@ -0,0 +1,145 @@
+use std::collections::{HashMap, VecDeque};
+use image::{Frame, Rgba, RgbaImage};
+
+// Largest possible distance between two RGB colors
+const MAX_DISTANCE: f32 = 441.673;
+
+pub fn chroma(frames: &mut [Frame], color: Option<Rgba<u8>>, tolerance: f32) {
+    // Without a color, key out the most common color around the edges, which is nothing if they're already transparent
+    let Some(key) = color.or_else(|| frames.first().and_then(|frame| get_border_color(frame.buffer()))) else { return };
+    let (inner, outer) = get_thresholds(tolerance);
+
+    for frame in frames {
+        let buffer = frame.buffer_mut();
+
+        for pixel in buffer.pixels_mut() {
+            if pixel.0[3] == 0 { continue; }
+
+            let coverage = get_coverage(distance(pixel, &key), inner, outer);
+            remove_key(pixel, &key, coverage);
+        }
+    }
+}
+
+pub fn remove_background(frames: &mut [Frame], tolerance: f32) {
+    let (inner, outer) = get_thresholds(tolerance);
+
+    for frame in frames {
+        let buffer = frame.buffer_mut();
+        let Some(key) = get_border_color(buffer) else { continue };
+
+        let background = flood_fill(buffer, &key, inner);
+        let width = buffer.width() as usize;
+        let height = buffer.height() as usize;
+
+        for (index, pixel) in buffer.pixels_mut().enumerate() {
+            if background[index] {
+                *pixel = Rgba([0, 0, 0, 0]);
+                continue;
+            }
+
+            // Soften the edge of the filled area, leaving the inside of the emote alone
+            let x = index % width;
+            let y = index / width;
+            let touches_background = (x > 0 && background[index - 1])
+                || (x + 1 < width && background[index + 1])
+                || (y > 0 && background[index - width])
+                || (y + 1 < height && background[index + width]);
+
+            if touches_background {
+                let coverage = get_coverage(distance(pixel, &key), inner, outer);
+                remove_key(pixel, &key, coverage);
+            }
+        }
+    }
+}
+
+fn get_thresholds(tolerance: f32) -> (f32, f32) {
+    // Colors within the tolerance are removed, up to twice the tolerance they fade out
+    let inner = tolerance.clamp(0.0, 100.0) / 100.0 * MAX_DISTANCE;
+    (inner, (inner * 2.0).max(inner + 1.0))
+}
+
+fn get_coverage(distance: f32, inner: f32, outer: f32) -> f32 {
+    ((distance - inner) / (outer - inner)).clamp(0.0, 1.0)
+}
+
+fn distance(pixel: &Rgba<u8>, key: &Rgba<u8>) -> f32 {
+    let [red, green, blue] = [0, 1, 2].map(|channel| pixel.0[channel] as f32 - key.0[channel] as f32);
+    (red * red + green * green + blue * blue).sqrt()
+}
+
+fn remove_key(pixel: &mut Rgba<u8>, key: &Rgba<u8>, coverage: f32) {
+    if coverage >= 1.0 { return; }
+    if coverage <= 0.0 {
+        *pixel = Rgba([0, 0, 0, 0]);
+        return;
+    }
+
+    // Edge pixels are a blend with the key color, take it back out so they don't keep a colored fringe
+    for channel in 0..3 {
+        let value = pixel.0[channel] as f32;
+        let color = (value - key.0[channel] as f32 * (1.0 - coverage)) / coverage;
+        pixel.0[channel] = color.round().clamp(0.0, 255.0) as u8;
+    }
+
+    pixel.0[3] = (pixel.0[3] as f32 * coverage).round() as u8;
+}
+
+fn get_border_color(buffer: &RgbaImage) -> Option<Rgba<u8>> {
+    let width = buffer.width();
+    let height = buffer.height();
+
+    let border = (0..width)
+        .flat_map(|x| [(x, 0), (x, height - 1)])
+        .chain((0..height).flat_map(|y| [(0, y), (width - 1, y)]))
+        .map(|(x, y)| buffer.get_pixel(x, y))
+        .filter(|pixel| pixel.0[3] != 0);
+
+    // Group similar colors together and pick the most common one, averaged to undo the grouping
+    let mut buckets: HashMap<[u8; 3], (u32, [u32; 3])> = HashMap::new();
+    for pixel in border {
+        let bucket = buckets.entry([pixel.0[0] >> 4, pixel.0[1] >> 4, pixel.0[2] >> 4]).or_default();
+        bucket.0 += 1;
+
+        for channel in 0..3 {
+            bucket.1[channel] += pixel.0[channel] as u32;
+        }
+    }
+
+    let (count, sums) = buckets.into_values().max_by_key(|(count, _)| *count)?;
+    let [red, green, blue] = sums.map(|sum| (sum / count) as u8);
+
+    Some(Rgba([red, green, blue, 255]))
+}
+
+fn flood_fill(buffer: &RgbaImage, key: &Rgba<u8>, tolerance: f32) -> Vec<bool> {
+    let width = buffer.width() as usize;
+    let height = buffer.height() as usize;
+
+    let is_background = |index: usize| {
+        let pixel = buffer.get_pixel((index % width) as u32, (index / width) as u32);
+        pixel.0[3] == 0 || distance(pixel, key) <= tolerance
+    };
+
+    let mut background = vec![false; width * height];
+    let mut queue: VecDeque<usize> = (0..width)
+        .flat_map(|x| [x, (height - 1) * width + x])
+        .chain((0..height).flat_map(|y| [y * width, y * width + width - 1]))
+        .collect();
+
+    while let Some(index) = queue.pop_front() {
+        if background[index] || !is_background(index) { continue; }
+        background[index] = true;
+
+        let x = index % width;
+        let y = index / width;
+
+        if x > 0 { queue.push_back(index - 1); }
+        if x + 1 < width { queue.push_back(index + 1); }
+        if y > 0 { queue.push_back(index - width); }
+        if y + 1 < height { queue.push_back(index + width); }
+    }
+
+    background
+}
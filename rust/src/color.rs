@@ -0,0 +1,101 @@
+use colors_transform::{Color, Rgb};
+use image::{Frame, Rgba};
+
+#[derive(Copy, Clone)]
+pub enum ColorFilter {
+    Grayscale,
+    Sepia,
+    Invert,
+    Tint(Rgba<u8>),
+    Saturation(f32),
+    Brightness(f32),
+    Contrast(f32),
+    HueRotate(f32),
+}
+
+pub fn color_filter(frames: &mut [Frame], filter: ColorFilter) {
+    for frame in frames {
+        for pixel in frame.buffer_mut().pixels_mut() {
+            if pixel.0[3] == 0 { continue; }
+            filter_pixel(pixel, filter);
+        }
+    }
+}
+
+pub fn filter_pixel(pixel: &mut Rgba<u8>, filter: ColorFilter) {
+    let [red, green, blue, _] = pixel.0.map(|channel| channel as f32);
+
+    let (red, green, blue) = match filter {
+        ColorFilter::Grayscale => {
+            let luma = get_luma(red, green, blue);
+            (luma, luma, luma)
+        },
+        ColorFilter::Sepia => (
+            red * 0.393 + green * 0.769 + blue * 0.189,
+            red * 0.349 + green * 0.686 + blue * 0.168,
+            red * 0.272 + green * 0.534 + blue * 0.131,
+        ),
+        ColorFilter::Invert => (255.0 - red, 255.0 - green, 255.0 - blue),
+        ColorFilter::Tint(color) => {
+            // Keep the shading but replace the color
+            let luma = get_luma(red, green, blue) / 255.0;
+            (color.0[0] as f32 * luma, color.0[1] as f32 * luma, color.0[2] as f32 * luma)
+        },
+        ColorFilter::Saturation(amount) => {
+            let luma = get_luma(red, green, blue);
+            (
+                luma + (red - luma) * amount,
+                luma + (green - luma) * amount,
+                luma + (blue - luma) * amount,
+            )
+        },
+        ColorFilter::Brightness(amount) => (red * amount, green * amount, blue * amount),
+        ColorFilter::Contrast(amount) => (
+            (red - 128.0) * amount + 128.0,
+            (green - 128.0) * amount + 128.0,
+            (blue - 128.0) * amount + 128.0,
+        ),
+        ColorFilter::HueRotate(degrees) => {
+            let mut hsl = Rgb::from(red, green, blue).to_hsl().adjust_hue(degrees.rem_euclid(360.0));
+
+            while hsl.get_hue() > 360.0 {
+                hsl = hsl.adjust_hue(-360.0);
+            }
+
+            hsl.to_rgb().as_tuple()
+        },
+    };
+
+    pixel.0[0] = red.round().clamp(0.0, 255.0) as u8;
+    pixel.0[1] = green.round().clamp(0.0, 255.0) as u8;
+    pixel.0[2] = blue.round().clamp(0.0, 255.0) as u8;
+}
+
+pub fn parse_color(value: &str) -> Option<Rgba<u8>> {
+    let color = match value {
+        "red" => [255, 0, 0],
+        "orange" => [255, 140, 0],
+        "yellow" => [255, 220, 0],
+        "green" => [0, 200, 0],
+        "cyan" => [0, 220, 220],
+        "blue" => [0, 120, 255],
+        "purple" => [150, 50, 220],
+        "pink" => [255, 105, 180],
+        "white" => [255, 255, 255],
+        "gray" => [128, 128, 128],
+        "black" => [0, 0, 0],
+        hex => {
+            let hex = hex.strip_prefix('#').unwrap_or(hex);
+            if hex.len() != 6 { return None; }
+
+            let channel = |index: usize| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok();
+            [channel(0)?, channel(2)?, channel(4)?]
+        },
+    };
+
+    Some(Rgba([color[0], color[1], color[2], 255]))
+}
+
+fn get_luma(red: f32, green: f32, blue: f32) -> f32 {
+    red * 0.299 + green * 0.587 + blue * 0.114
+}
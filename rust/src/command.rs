@@ -1,14 +1,38 @@
 use std::fmt::{Formatter, Result as FmtResult};
 
+use image::Rgba;
 use serde::{
     de::{Error as DeError, MapAccess, Visitor},
     Deserialize, Deserializer,
 };
 
+use crate::color::parse_color;
+
 pub struct Command {
     pub name: String,
     pub param: f32,
     pub param_extra: Option<f32>,
+    pub options: Vec<String>,
+    pub data: Option<Vec<u8>>,
+    pub text: Option<String>,
+}
+
+impl Command {
+    pub fn has_option(&self, option: &str) -> bool {
+        self.options.iter().any(|item| item == option)
+    }
+
+    // Numeric options are written as the option name followed by its value, e.g. layers8
+    pub fn option_value(&self, option: &str) -> Option<f32> {
+        self.options
+            .iter()
+            .find_map(|item| item.strip_prefix(option)?.parse().ok())
+            .filter(|value: &f32| value.is_finite())
+    }
+
+    pub fn color_option(&self) -> Option<Rgba<u8>> {
+        self.options.iter().find_map(|item| parse_color(item))
+    }
 }
 
 impl<'de> Deserialize<'de> for Command {
@@ -27,6 +51,9 @@ impl<'de> Deserialize<'de> for Command {
                 let mut name: Option<String> = None;
                 let mut param: Option<f32> = None;
                 let mut param_extra: Option<f32> = None;
+                let mut options: Option<Vec<String>> = None;
+                let mut data: Option<Vec<u8>> = None;
+                let mut text: Option<String> = None;
 
                 // serde_wasm_bindgen's Deserializer unfortunately only deals with allocated Strings
                 while let Some(key) = map.next_key::<String>()? {
@@ -43,7 +70,10 @@ impl<'de> Deserialize<'de> for Command {
                                 param_extra = split.next().map(|item| js_sys::parse_float(item) as f32);
                             }
                         },
-                        other => return Err(DeError::unknown_field(other, &["name", "param"])),
+                        "options" => options = Some(map.next_value()?),
+                        "data" => data = Some(map.next_value()?),
+                        "text" => text = Some(map.next_value()?),
+                        other => return Err(DeError::unknown_field(other, &["name", "param", "options", "data", "text"])),
                     }
                 }
 
@@ -51,6 +81,9 @@ impl<'de> Deserialize<'de> for Command {
                     name: name.ok_or_else(|| DeError::missing_field("name"))?,
                     param: param.unwrap_or(0.0),
                     param_extra,
+                    options: options.unwrap_or_default(),
+                    data,
+                    text,
                 })
             }
         }
@@ -0,0 +1,385 @@
+use std::fmt::{Formatter, Result as FmtResult};
+
+use image::{imageops, Frame, RgbaImage};
+use serde::{
+    de::{Error as DeError, MapAccess, Visitor},
+    Deserialize, Deserializer,
+};
+
+use crate::{command::Command, resize::scale_buffer, utils::{get_delay, get_shown_delay_centisecs}};
+
+// Longest loop to build when the inputs only line up after a long time
+const MAX_LOOP_CENTISECS: u32 = 1000;
+const MAX_FRAMES: u32 = 500;
+const MIN_DELAY_CENTISECS: u32 = 2;
+
+pub struct Input {
+    pub data: Vec<u8>,
+    pub format_type: String,
+    pub commands: Vec<Command>,
+}
+
+impl<'de> Deserialize<'de> for Input {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        struct InputVisitor;
+
+        impl<'de> Visitor<'de> for InputVisitor {
+            type Value = Input;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> FmtResult {
+                f.write_str("an Input")
+            }
+
+            #[inline]
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut data: Option<Vec<u8>> = None;
+                let mut format_type: Option<String> = None;
+                let mut commands: Option<Vec<Command>> = None;
+
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "data" => data = Some(map.next_value()?),
+                        "formatType" => format_type = Some(map.next_value()?),
+                        "commands" => commands = Some(map.next_value()?),
+                        other => return Err(DeError::unknown_field(other, &["data", "formatType", "commands"])),
+                    }
+                }
+
+                Ok(Input {
+                    data: data.ok_or_else(|| DeError::missing_field("data"))?,
+                    format_type: format_type.ok_or_else(|| DeError::missing_field("formatType"))?,
+                    commands: commands.unwrap_or_default(),
+                })
+            }
+        }
+
+        d.deserialize_map(InputVisitor)
+    }
+}
+
+#[derive(Copy, Clone)]
+pub enum Anchor {
+    Center,
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Anchor {
+    // Horizontal and vertical position, 0 being left or top and 1 being right or bottom
+    pub fn position(self) -> (f32, f32) {
+        match self {
+            Anchor::Center => (0.5, 0.5),
+            Anchor::Top => (0.5, 0.0),
+            Anchor::Bottom => (0.5, 1.0),
+            Anchor::Left => (0.0, 0.5),
+            Anchor::Right => (1.0, 0.5),
+            Anchor::TopLeft => (0.0, 0.0),
+            Anchor::TopRight => (1.0, 0.0),
+            Anchor::BottomLeft => (0.0, 1.0),
+            Anchor::BottomRight => (1.0, 1.0),
+        }
+    }
+
+    pub fn from_options(command: &Command) -> Option<Self> {
+        command.options.iter().find_map(|option| match option.as_str() {
+            "center" => Some(Anchor::Center),
+            "top" => Some(Anchor::Top),
+            "bottom" => Some(Anchor::Bottom),
+            "left" => Some(Anchor::Left),
+            "right" => Some(Anchor::Right),
+            "topleft" => Some(Anchor::TopLeft),
+            "topright" => Some(Anchor::TopRight),
+            "bottomleft" => Some(Anchor::BottomLeft),
+            "bottomright" => Some(Anchor::BottomRight),
+            _ => None,
+        })
+    }
+}
+
+impl From<&Command> for Anchor {
+    fn from(command: &Command) -> Self {
+        Anchor::from_options(command).unwrap_or(Anchor::BottomRight)
+    }
+}
+
+#[derive(Copy, Clone)]
+pub enum Layout {
+    Horizontal,
+    Vertical,
+    // Amount of columns, 0 picks a square-ish grid
+    Grid(u32),
+    // Later inputs are placed on the first one, scaled in percent of its size
+    Overlay(Anchor, f32),
+}
+
+impl From<&Command> for Layout {
+    fn from(command: &Command) -> Self {
+        match command.name.as_str() {
+            "vertical" => Layout::Vertical,
+            "grid" => Layout::Grid(command.param.max(0.0) as u32),
+            "overlay" => {
+                let scale = if command.param > 0.0 { command.param.min(100.0) } else { 50.0 };
+                Layout::Overlay(command.into(), scale)
+            },
+            _ => Layout::Horizontal,
+        }
+    }
+}
+
+struct Placement {
+    x: i64,
+    y: i64,
+    scale: f32,
+}
+
+pub fn compose(inputs: Vec<Vec<Frame>>, layout: Layout) -> Vec<Frame> {
+    let inputs: Vec<Vec<Frame>> = inputs.into_iter().filter(|frames| !frames.is_empty()).collect();
+    let sizes: Vec<(u32, u32)> = inputs
+        .iter()
+        .map(|frames| (frames[0].buffer().width(), frames[0].buffer().height()))
+        .collect();
+
+    let Some((width, height, placements)) = get_placements(&sizes, layout) else { return vec![] };
+    let (delays, times) = get_timeline(&inputs);
+
+    delays
+        .into_iter()
+        .zip(times)
+        .map(|(delay, time)| {
+            let mut canvas = RgbaImage::new(width, height);
+
+            for (frames, placement) in inputs.iter().zip(&placements) {
+                let buffer = get_frame_at(frames, time).buffer();
+
+                if placement.scale == 1.0 {
+                    imageops::overlay(&mut canvas, buffer, placement.x, placement.y);
+                } else {
+                    let scaled_buffer = scale_buffer(buffer, placement.scale, placement.scale);
+                    imageops::overlay(&mut canvas, &scaled_buffer, placement.x, placement.y);
+                }
+            }
+
+            Frame::from_parts(canvas, 0, 0, get_delay(delay))
+        })
+        .collect()
+}
+
+fn get_placements(sizes: &[(u32, u32)], layout: Layout) -> Option<(u32, u32, Vec<Placement>)> {
+    // The first emote decides the size everything else is matched to
+    let &(base_width, base_height) = sizes.first()?;
+    let (base_width, base_height) = (base_width as f32, base_height as f32);
+
+    let mut placements = Vec::with_capacity(sizes.len());
+    let (mut width, mut height) = (0.0_f32, 0.0_f32);
+
+    match layout {
+        Layout::Horizontal => {
+            for &(input_width, input_height) in sizes {
+                let scale = base_height / input_height as f32;
+                placements.push(Placement { x: width.round() as i64, y: 0, scale });
+
+                width += (input_width as f32 * scale).round().max(1.0);
+            }
+
+            height = base_height;
+        },
+        Layout::Vertical => {
+            for &(input_width, input_height) in sizes {
+                let scale = base_width / input_width as f32;
+                placements.push(Placement { x: 0, y: height.round() as i64, scale });
+
+                height += (input_height as f32 * scale).round().max(1.0);
+            }
+
+            width = base_width;
+        },
+        Layout::Grid(columns) => {
+            let columns = match columns {
+                0 => (sizes.len() as f32).sqrt().ceil() as usize,
+                columns => (columns as usize).min(sizes.len()),
+            };
+            let rows = sizes.len().div_ceil(columns);
+
+            // Every emote is fit into a cell the size of the first one, centered
+            for (index, &(input_width, input_height)) in sizes.iter().enumerate() {
+                let scale = (base_width / input_width as f32).min(base_height / input_height as f32);
+                let scaled_width = (input_width as f32 * scale).round();
+                let scaled_height = (input_height as f32 * scale).round();
+
+                let cell_x = (index % columns) as f32 * base_width;
+                let cell_y = (index / columns) as f32 * base_height;
+
+                placements.push(Placement {
+                    x: (cell_x + (base_width - scaled_width) / 2.0).round() as i64,
+                    y: (cell_y + (base_height - scaled_height) / 2.0).round() as i64,
+                    scale,
+                });
+            }
+
+            width = columns as f32 * base_width;
+            height = rows as f32 * base_height;
+        },
+        Layout::Overlay(anchor, scale) => {
+            let (anchor_x, anchor_y) = anchor.position();
+            placements.push(Placement { x: 0, y: 0, scale: 1.0 });
+
+            for &(input_width, input_height) in &sizes[1..] {
+                let fit = (base_width / input_width as f32).min(base_height / input_height as f32);
+                let scale = fit * scale / 100.0;
+
+                let scaled_width = (input_width as f32 * scale).round();
+                let scaled_height = (input_height as f32 * scale).round();
+
+                placements.push(Placement {
+                    x: ((base_width - scaled_width) * anchor_x).round() as i64,
+                    y: ((base_height - scaled_height) * anchor_y).round() as i64,
+                    scale,
+                });
+            }
+
+            width = base_width;
+            height = base_height;
+        },
+    }
+
+    Some((width as u32, height as u32, placements))
+}
+
+fn get_timeline(inputs: &[Vec<Frame>]) -> (Vec<u32>, Vec<u32>) {
+    let animated: Vec<&Vec<Frame>> = inputs.iter().filter(|frames| frames.len() > 1).collect();
+    if animated.is_empty() { return (vec![MIN_DELAY_CENTISECS], vec![0]); }
+
+    // Play until every emote lines up with the start of its loop again, if that doesn't take too long
+    let durations: Vec<u32> = animated.iter().map(|frames| get_duration(frames)).collect();
+    let longest = durations.iter().copied().max().unwrap_or(MIN_DELAY_CENTISECS);
+    let loop_duration = durations
+        .iter()
+        .try_fold(1, |total, &duration| Some(lcm(total, duration)).filter(|&total| total <= MAX_LOOP_CENTISECS))
+        .unwrap_or(longest);
+
+    // Step through time as fast as the fastest emote changes frames
+    let step = animated
+        .iter()
+        .flat_map(|frames| frames.iter())
+        .map(|frame| get_shown_delay_centisecs(frame.delay()).round() as u32)
+        .min()
+        .unwrap_or(MIN_DELAY_CENTISECS)
+        .max(MIN_DELAY_CENTISECS)
+        .max(loop_duration.div_ceil(MAX_FRAMES));
+
+    // Round the frame count down so rounding the times never leaves a frame shorter than the step
+    let frame_count = (loop_duration / step).max(1);
+    let times: Vec<u32> = (0..=frame_count)
+        .map(|index| (index as f32 * loop_duration as f32 / frame_count as f32).round() as u32)
+        .collect();
+
+    let delays = times
+        .windows(2)
+        .map(|window| (window[1] - window[0]).max(MIN_DELAY_CENTISECS))
+        .collect();
+    (delays, times)
+}
+
+fn get_frame_at(frames: &[Frame], time: u32) -> &Frame {
+    if frames.len() == 1 { return &frames[0]; }
+
+    let time = time % get_duration(frames);
+    let mut elapsed = 0;
+
+    for frame in frames {
+        elapsed += get_shown_delay_centisecs(frame.delay()).round() as u32;
+        if time < elapsed { return frame; }
+    }
+
+    &frames[frames.len() - 1]
+}
+
+fn get_duration(frames: &[Frame]) -> u32 {
+    frames
+        .iter()
+        .map(|frame| get_shown_delay_centisecs(frame.delay()).round() as u32)
+        .sum::<u32>()
+        .max(1)
+}
+
+fn lcm(first: u32, second: u32) -> u32 {
+    let (mut a, mut b) = (first, second);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    first / a * second
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::blank_frames;
+
+    #[test]
+    fn timeline_keeps_minimum_delay() {
+        let mixed = [blank_frames(1, 2), blank_frames(1, 3)].concat();
+        let (delays, times) = get_timeline(&[blank_frames(1, 2), mixed]);
+
+        assert_eq!(delays, vec![3, 2]);
+        assert_eq!(times, vec![0, 3, 5]);
+    }
+
+    #[test]
+    fn timeline_plays_zero_delay_like_browsers() {
+        let frames = blank_frames(3, 0);
+        let (delays, _) = get_timeline(&[blank_frames(1, 2), frames.clone()]);
+
+        assert_eq!(delays, vec![10; 3]);
+        assert!(std::ptr::eq(get_frame_at(&frames, 10), &frames[1]));
+    }
+
+    #[test]
+    fn timeline_lines_up_loops() {
+        let (delays, times) = get_timeline(&[blank_frames(2, 10), blank_frames(3, 10)]);
+
+        assert_eq!(delays, vec![10; 6]);
+        assert_eq!(times.last(), Some(&60));
+    }
+
+    #[test]
+    fn timeline_falls_back_to_longest_loop() {
+        // 14, 22 and 26cs only line up after 2002cs, which is too long
+        let (delays, _) = get_timeline(&[blank_frames(2, 7), blank_frames(2, 11), blank_frames(2, 13)]);
+        let duration: u32 = delays.iter().sum();
+
+        assert_eq!(duration, 26);
+        assert!(delays.iter().all(|&delay| delay >= MIN_DELAY_CENTISECS));
+    }
+
+    #[test]
+    fn horizontal_placements_match_first_height() {
+        let (width, height, placements) = get_placements(&[(48, 48), (24, 48), (48, 24)], Layout::Horizontal).unwrap();
+
+        assert_eq!((width, height), (168, 48));
+        assert_eq!(placements.iter().map(|placement| placement.x).collect::<Vec<_>>(), vec![0, 48, 72]);
+        assert_eq!(placements[2].scale, 2.0);
+    }
+
+    #[test]
+    fn grid_placements_center_in_cells() {
+        let (width, height, placements) = get_placements(&[(48, 48), (48, 48), (48, 24)], Layout::Grid(0)).unwrap();
+
+        assert_eq!((width, height), (96, 96));
+        assert_eq!((placements[1].x, placements[1].y), (48, 0));
+        assert_eq!((placements[2].x, placements[2].y), (0, 60));
+    }
+
+    #[test]
+    fn overlay_placements_use_anchor() {
+        let (_, _, placements) = get_placements(&[(48, 48), (48, 48)], Layout::Overlay(Anchor::BottomRight, 50.0)).unwrap();
+
+        assert_eq!((placements[1].x, placements[1].y, placements[1].scale), (24, 24, 0.5));
+    }
+}
@@ -0,0 +1,97 @@
+use image::{Frame, GenericImageView, Rgba, RgbaImage};
+use imageproc::{filter::filter3x3, noise::gaussian_noise_mut};
+
+use crate::{color::{filter_pixel, ColorFilter}, utils::get_random_u32};
+
+const SHARPEN_KERNEL: [f32; 9] = [0.0, -1.0, 0.0, -1.0, 5.0, -1.0, 0.0, -1.0, 0.0];
+// JPEG compresses in blocks of this size
+const BLOCK_SIZE: u32 = 8;
+
+pub fn deepfry(frames: &mut [Frame], intensity: f32) {
+    let level = intensity.clamp(1.0, 3.0);
+
+    for frame in frames {
+        let buffer = frame.buffer_mut();
+
+        fry_colors(buffer, level);
+        sharpen(buffer);
+        add_noise(buffer, level);
+        compress_blocks(buffer, level);
+    }
+}
+
+fn fry_colors(buffer: &mut RgbaImage, level: f32) {
+    for pixel in buffer.pixels_mut() {
+        if pixel.0[3] == 0 { continue; }
+
+        filter_pixel(pixel, ColorFilter::Saturation(1.0 + level));
+        filter_pixel(pixel, ColorFilter::Contrast(1.0 + level / 2.0));
+
+        // Warm everything up towards orange
+        pixel.0[0] = (pixel.0[0] as f32 * 1.15).min(255.0) as u8;
+        pixel.0[2] = (pixel.0[2] as f32 * (1.0 - 0.1 * level)) as u8;
+    }
+}
+
+fn sharpen(buffer: &mut RgbaImage) {
+    let sharpened: RgbaImage = filter3x3(buffer, &SHARPEN_KERNEL);
+    copy_colors(buffer, &sharpened);
+}
+
+fn add_noise(buffer: &mut RgbaImage, level: f32) {
+    let mut noisy = buffer.clone();
+    let seed = get_random_u32(0, u32::MAX) as u64;
+
+    gaussian_noise_mut(&mut noisy, 0.0, 8.0 * level as f64, seed);
+    copy_colors(buffer, &noisy);
+}
+
+fn compress_blocks(buffer: &mut RgbaImage, level: f32) {
+    let width = buffer.width();
+    let height = buffer.height();
+    let blend = 0.15 * level;
+    let step = 8.0 * level;
+
+    for block_y in (0..height).step_by(BLOCK_SIZE as usize) {
+        for block_x in (0..width).step_by(BLOCK_SIZE as usize) {
+            let block_width = BLOCK_SIZE.min(width - block_x);
+            let block_height = BLOCK_SIZE.min(height - block_y);
+            let Some(average) = get_average(&buffer.view(block_x, block_y, block_width, block_height).to_image()) else { continue };
+
+            for y in block_y..block_y + block_height {
+                for x in block_x..block_x + block_width {
+                    let pixel = buffer.get_pixel_mut(x, y);
+                    if pixel.0[3] == 0 { continue; }
+
+                    // Smear towards the block color and lose color precision
+                    for (channel, average) in pixel.0.iter_mut().zip(average) {
+                        let value = *channel as f32 * (1.0 - blend) + average * blend;
+                        *channel = ((value / step).round() * step).clamp(0.0, 255.0) as u8;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn get_average(block: &RgbaImage) -> Option<[f32; 3]> {
+    let opaque: Vec<&Rgba<u8>> = block.pixels().filter(|pixel| pixel.0[3] != 0).collect();
+    if opaque.is_empty() { return None; }
+
+    let mut average = [0.0; 3];
+    for pixel in &opaque {
+        for (channel, value) in average.iter_mut().enumerate() {
+            *value += pixel.0[channel] as f32;
+        }
+    }
+
+    Some(average.map(|value| value / opaque.len() as f32))
+}
+
+fn copy_colors(buffer: &mut RgbaImage, source: &RgbaImage) {
+    // Only take the colors so transparency stays untouched
+    for (pixel, source) in buffer.pixels_mut().zip(source.pixels()) {
+        if pixel.0[3] == 0 { continue; }
+        pixel.0[..3].copy_from_slice(&source.0[..3]);
+    }
+}
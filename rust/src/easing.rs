@@ -0,0 +1,75 @@
+use std::f32::consts::PI;
+
+use crate::command::Command;
+
+#[derive(Copy, Clone)]
+pub enum Easing {
+    Linear,
+    EaseInOut,
+    Bounce,
+    Elastic,
+    Step,
+}
+
+impl Easing {
+    // Maps linear progress through a cycle (0 to 1) onto eased progress, which may overshoot
+    pub fn apply(self, progress: f32) -> f32 {
+        match self {
+            Easing::Linear => progress,
+            Easing::EaseInOut => ease_in_out(progress),
+            Easing::Bounce => bounce(progress),
+            Easing::Elastic => elastic(progress),
+            Easing::Step => (progress * 4.0).floor() / 4.0,
+        }
+    }
+}
+
+impl From<&Command> for Easing {
+    fn from(command: &Command) -> Self {
+        command.options.iter().find_map(|option| match option.as_str() {
+            "linear" => Some(Easing::Linear),
+            "ease" => Some(Easing::EaseInOut),
+            "bounce" => Some(Easing::Bounce),
+            "elastic" => Some(Easing::Elastic),
+            "step" => Some(Easing::Step),
+            _ => None,
+        }).unwrap_or(Easing::Linear)
+    }
+}
+
+pub fn get_progress(index: usize, step: f32) -> f32 {
+    (index as f32 * step) % 1.0
+}
+
+fn ease_in_out(progress: f32) -> f32 {
+    if progress < 0.5 {
+        4.0 * progress.powi(3)
+    } else {
+        1.0 - (-2.0 * progress + 2.0).powi(3) / 2.0
+    }
+}
+
+fn bounce(progress: f32) -> f32 {
+    let strength = 7.5625;
+    let width = 2.75;
+
+    if progress < 1.0 / width {
+        strength * progress.powi(2)
+    } else if progress < 2.0 / width {
+        let progress = progress - 1.5 / width;
+        strength * progress.powi(2) + 0.75
+    } else if progress < 2.5 / width {
+        let progress = progress - 2.25 / width;
+        strength * progress.powi(2) + 0.9375
+    } else {
+        let progress = progress - 2.625 / width;
+        strength * progress.powi(2) + 0.984375
+    }
+}
+
+fn elastic(progress: f32) -> f32 {
+    if progress <= 0.0 || progress >= 1.0 { return progress; }
+
+    let period = (2.0 * PI) / 3.0;
+    2.0_f32.powf(-10.0 * progress) * ((progress * 10.0 - 0.75) * period).sin() + 1.0
+}
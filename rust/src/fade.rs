@@ -0,0 +1,66 @@
+use std::f32::consts::PI;
+use image::{Frame, RgbaImage};
+
+use crate::{easing::get_progress, utils::{align_gif, align_speed, get_delay_centisecs}};
+
+#[derive(Copy, Clone)]
+pub enum Fade {
+    Loop,
+    In,
+    Out,
+    Blink,
+}
+
+pub fn fade(frames: &mut Vec<Frame>, speed: f32, fade: Fade) {
+    align_speed(frames, 6.0);
+    let Some(frame) = frames.first() else { return };
+
+    let delay_centisecs = get_delay_centisecs(frame.delay());
+    let centisecs_per_fade = (100.0 * speed) / 8.0;
+    let fade_step = delay_centisecs / centisecs_per_fade;
+    let interval = (1.0 / fade_step).floor().max(1.0) as usize;
+
+    match fade {
+        Fade::Loop | Fade::Blink => {
+            *frames = align_gif(frames, interval);
+
+            for (index, frame) in frames.iter_mut().enumerate() {
+                let progress = get_progress(index, fade_step);
+                let opacity = match fade {
+                    Fade::Blink => if progress < 0.5 { 1.0 } else { 0.0 },
+                    _ => (1.0 - (2.0 * PI * progress).cos()) / 2.0,
+                };
+
+                fade_buffer(frame.buffer_mut(), opacity);
+            }
+        },
+        Fade::In | Fade::Out => {
+            // Only fades once, so just make sure there are enough frames to fit it
+            if frames.len() < interval {
+                *frames = align_gif(frames, interval);
+            }
+
+            let frame_count = frames.len();
+            let fade_start = match fade {
+                Fade::In => 0,
+                _ => frame_count - interval,
+            };
+
+            for (index, frame) in frames.iter_mut().enumerate().skip(fade_start).take(interval) {
+                let progress = (index - fade_start) as f32 / (interval - 1).max(1) as f32;
+                let opacity = match fade {
+                    Fade::In => progress,
+                    _ => 1.0 - progress,
+                };
+
+                fade_buffer(frame.buffer_mut(), opacity);
+            }
+        },
+    }
+}
+
+pub fn fade_buffer(buffer: &mut RgbaImage, opacity: f32) {
+    for pixel in buffer.pixels_mut() {
+        pixel.0[3] = (pixel.0[3] as f32 * opacity).round() as u8;
+    }
+}
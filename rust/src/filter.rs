@@ -0,0 +1,191 @@
+use image::{Frame, Rgba, Rgba32FImage, RgbaImage};
+use imageproc::filter::separable_filter_equal;
+
+use crate::{alpha::from_premultiplied, command::Command, easing::{get_progress, Easing}, utils::{align_gif, align_speed, get_delay_centisecs}};
+
+const DEFAULT_BLOCK_SIZE: f32 = 6.0;
+const MAX_BLOCK_SIZE: f32 = 64.0;
+const MAX_BLUR_RADIUS: f32 = 32.0;
+// Part of the cycle spent changing the pixelation, the rest is spent holding the end result
+const TRANSITION: f32 = 0.7;
+// Sharpening compares every pixel against a blur this wide
+const SHARPEN_SIGMA: f32 = 1.0;
+
+#[derive(Copy, Clone)]
+pub enum Animation {
+    Still,
+    // Pixelates more and more until the emote is hidden
+    Censor,
+    // Starts out pixelated and becomes sharp
+    Reveal,
+}
+
+#[derive(Copy, Clone)]
+pub struct PixelateOptions {
+    block_size: f32,
+    animation: Animation,
+}
+
+impl From<&Command> for PixelateOptions {
+    fn from(command: &Command) -> Self {
+        let block_size = command.options
+            .iter()
+            .find_map(|option| option.parse::<f32>().ok())
+            .map_or(DEFAULT_BLOCK_SIZE, |size| size.clamp(1.0, MAX_BLOCK_SIZE));
+
+        let animation = if command.has_option("censor") {
+            Animation::Censor
+        } else if command.has_option("reveal") {
+            Animation::Reveal
+        } else {
+            Animation::Still
+        };
+
+        Self {
+            block_size,
+            animation,
+        }
+    }
+}
+
+pub fn pixelate(frames: &mut Vec<Frame>, speed: f32, options: PixelateOptions) {
+    if let Animation::Still = options.animation {
+        for frame in frames {
+            pixelate_buffer(frame.buffer_mut(), options.block_size.round() as u32);
+        }
+        return;
+    }
+
+    align_speed(frames, 6.0);
+    let Some(frame) = frames.first() else { return };
+
+    let delay_centisecs = get_delay_centisecs(frame.delay());
+    let centisecs_per_cycle = (200.0 * speed) / 8.0;
+    let step = delay_centisecs / centisecs_per_cycle;
+    let interval = (1.0 / step).floor().max(1.0) as usize;
+
+    *frames = align_gif(frames, interval);
+
+    for (index, frame) in frames.iter_mut().enumerate() {
+        let progress = Easing::EaseInOut.apply((get_progress(index, step) / TRANSITION).min(1.0));
+        let amount = match options.animation {
+            Animation::Reveal => 1.0 - progress,
+            _ => progress,
+        };
+
+        let block_size = 1.0 + (options.block_size - 1.0) * amount;
+        pixelate_buffer(frame.buffer_mut(), block_size.round() as u32);
+    }
+}
+
+pub fn blur(frames: &mut [Frame], radius: f32) {
+    if radius <= 0.0 { return; }
+
+    // The radius covers about two standard deviations of the blur
+    let sigma = radius.min(MAX_BLUR_RADIUS) / 2.0;
+
+    for frame in frames {
+        let buffer = frame.buffer_mut();
+        let blurred = gaussian_blur(&premultiply(buffer), sigma);
+
+        *buffer = unpremultiply(&blurred);
+    }
+}
+
+pub fn sharpen(frames: &mut [Frame], amount: f32) {
+    if amount <= 0.0 { return; }
+
+    for frame in frames {
+        let buffer = frame.buffer_mut();
+        let blurred = unpremultiply(&gaussian_blur(&premultiply(buffer), SHARPEN_SIGMA));
+
+        // Unsharp mask, pushing every color away from its surroundings while leaving the alpha alone
+        for (pixel, blurred_pixel) in buffer.pixels_mut().zip(blurred.pixels()) {
+            if pixel.0[3] == 0 { continue; }
+
+            for (value, &surrounding) in pixel.0[..3].iter_mut().zip(&blurred_pixel.0[..3]) {
+                let sharpened = *value as f32 + (*value as f32 - surrounding as f32) * amount;
+                *value = sharpened.round().clamp(0.0, 255.0) as u8;
+            }
+        }
+    }
+}
+
+fn pixelate_buffer(buffer: &mut RgbaImage, block_size: u32) {
+    if block_size <= 1 { return; }
+
+    let width = buffer.width();
+    let height = buffer.height();
+
+    // Center the grid so the blocks on opposite edges are cut off evenly
+    let offset_x = (block_size - width % block_size) % block_size / 2;
+    let offset_y = (block_size - height % block_size) % block_size / 2;
+
+    for block_y in (0..height + offset_y).step_by(block_size as usize) {
+        for block_x in (0..width + offset_x).step_by(block_size as usize) {
+            let left = block_x.saturating_sub(offset_x);
+            let top = block_y.saturating_sub(offset_y);
+            let right = (block_x + block_size).saturating_sub(offset_x).min(width);
+            let bottom = (block_y + block_size).saturating_sub(offset_y).min(height);
+            if left >= right || top >= bottom { continue; }
+
+            let mut color = [0.0_f32; 3];
+            let mut alpha = 0.0_f32;
+
+            // Weigh colors by their alpha so transparent pixels don't darken the block
+            for y in top..bottom {
+                for x in left..right {
+                    let pixel = buffer.get_pixel(x, y);
+                    let opacity = pixel.0[3] as f32 / 255.0;
+
+                    for (value, &channel) in color.iter_mut().zip(&pixel.0[..3]) {
+                        *value += channel as f32 * opacity;
+                    }
+                    alpha += opacity;
+                }
+            }
+
+            // Dithering would break up the blocks, so each block is either shown or hidden
+            let count = ((right - left) * (bottom - top)) as f32;
+            let block = if alpha / count >= 0.5 {
+                from_premultiplied(color.map(|value| value / alpha), 1.0)
+            } else {
+                Rgba([0, 0, 0, 0])
+            };
+
+            for y in top..bottom {
+                for x in left..right {
+                    buffer.put_pixel(x, y, block);
+                }
+            }
+        }
+    }
+}
+
+fn gaussian_blur(image: &Rgba32FImage, sigma: f32) -> Rgba32FImage {
+    // imageproc's own kernel is cut off without being normalized, which would fade out solid areas
+    let radius = (3.0 * sigma).ceil() as i32;
+    let weights: Vec<f32> = (-radius..=radius)
+        .map(|offset| (-(offset * offset) as f32 / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let total: f32 = weights.iter().sum();
+    let kernel: Vec<f32> = weights.iter().map(|weight| weight / total).collect();
+
+    separable_filter_equal(image, &kernel)
+}
+
+fn premultiply(buffer: &RgbaImage) -> Rgba32FImage {
+    Rgba32FImage::from_fn(buffer.width(), buffer.height(), |x, y| {
+        let pixel = buffer.get_pixel(x, y);
+        let alpha = pixel.0[3] as f32 / 255.0;
+
+        Rgba([pixel.0[0] as f32 * alpha, pixel.0[1] as f32 * alpha, pixel.0[2] as f32 * alpha, alpha])
+    })
+}
+
+fn unpremultiply(image: &Rgba32FImage) -> RgbaImage {
+    RgbaImage::from_fn(image.width(), image.height(), |x, y| {
+        let pixel = image.get_pixel(x, y);
+        from_premultiplied([pixel.0[0], pixel.0[1], pixel.0[2]], pixel.0[3])
+    })
+}
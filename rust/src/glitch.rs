@@ -0,0 +1,102 @@
+use image::{Frame, Rgba, RgbaImage};
+
+use crate::{
+    command::Command,
+    slide::{rotate_vec, shift_frame_data, CHANNEL_COUNT},
+    utils::{align_gif, align_speed, get_delay_centisecs, SeededRng},
+};
+
+// Amount of different glitches before the animation loops
+const GLITCH_STATES: usize = 12;
+
+#[derive(Copy, Clone)]
+pub struct GlitchOptions {
+    intensity: f32,
+    seed: u64,
+}
+
+impl From<&Command> for GlitchOptions {
+    fn from(command: &Command) -> Self {
+        let intensity = command.option_value("intensity").map_or(1.0, |intensity| intensity.clamp(1.0, 3.0));
+        let seed = command.option_value("seed").map_or(0, |seed| seed.abs() as u64);
+
+        Self { intensity, seed }
+    }
+}
+
+pub fn glitch(frames: &mut Vec<Frame>, speed: f32, options: GlitchOptions) {
+    align_speed(frames, 4.0);
+    let Some(frame) = frames.first() else { return };
+
+    let delay_centisecs = get_delay_centisecs(frame.delay());
+    let centisecs_per_glitch = (16.0 * speed) / 8.0;
+    // Keep every glitch on screen for a few frames
+    let hold = (centisecs_per_glitch / delay_centisecs).round().max(1.0) as usize;
+
+    *frames = align_gif(frames, hold * GLITCH_STATES);
+
+    for (index, frame) in frames.iter_mut().enumerate() {
+        // Same state always gives the same glitch, regardless of which frame it lands on
+        let state = (index / hold) % GLITCH_STATES;
+        let mut rng = SeededRng::new(options.seed.wrapping_mul(GLITCH_STATES as u64).wrapping_add(state as u64));
+
+        let burst = rng.next_f32() < 0.15 + 0.1 * options.intensity;
+        glitch_frame(frame.buffer_mut(), &mut rng, options.intensity, burst);
+    }
+}
+
+fn glitch_frame(buffer: &mut RgbaImage, rng: &mut SeededRng, intensity: f32, burst: bool) {
+    let width = buffer.width();
+    let height = buffer.height();
+    let row_len = width as usize * CHANNEL_COUNT;
+
+    let (slices, max_shift, split) = if burst {
+        (rng.range(2, 4 + 2 * intensity as u32), 0.15 * intensity, 0.04 * intensity)
+    } else {
+        (rng.range(0, 2), 0.03 * intensity, 0.01 * intensity)
+    };
+
+    let max_slice_height = (height / 8).max(2);
+    let max_shift = (width as f32 * max_shift).round().max(2.0) as u32;
+
+    for _ in 0..slices {
+        let start = rng.range(0, height) as usize;
+        let end = (start + rng.range(1, max_slice_height) as usize).min(height as usize);
+        let shift = rng.range(1, max_shift) as usize;
+        let direction = if rng.range(0, 2) == 0 { 1 } else { -1 };
+
+        shift_frame_data(
+            &mut buffer.as_mut()[start * row_len..end * row_len],
+            shift * CHANNEL_COUNT,
+            row_len,
+            rotate_vec(direction),
+        );
+    }
+
+    let split = (width as f32 * split).round() as i64;
+    split_channels(buffer, split);
+}
+
+fn split_channels(buffer: &mut RgbaImage, split: i64) {
+    if split == 0 { return; }
+
+    let source = buffer.clone();
+    let width = buffer.width() as i64;
+
+    for (x, y, pixel) in buffer.enumerate_pixels_mut() {
+        // Red moves right, blue moves left and green stays in place
+        let red = get_shifted(&source, x as i64 - split, y, width);
+        let blue = get_shifted(&source, x as i64 + split, y, width);
+        let alpha = pixel.0[3].max(red.0[3]).max(blue.0[3]);
+
+        pixel.0 = [red.0[0], pixel.0[1], blue.0[2], alpha];
+    }
+}
+
+fn get_shifted(source: &RgbaImage, x: i64, y: u32, width: i64) -> Rgba<u8> {
+    if x < 0 || x >= width {
+        return Rgba([0, 0, 0, 0]);
+    }
+
+    *source.get_pixel(x as u32, y)
+}
@@ -3,55 +3,75 @@ use image::{
     Frame, RgbaImage,
 };
 
-use crate::utils::{align_gif, align_speed, get_delay_centisecs};
+use crate::{command::Command, easing::Easing, fade::fade_buffer, utils::{align_gif, align_speed, get_delay_centisecs, overlay_centered}};
 
-pub fn infinite(frames: &mut Vec<Frame>, speed: f32)  {
+#[derive(Copy, Clone)]
+pub enum Direction {
+    Outwards,
+    Inwards,
+}
+
+#[derive(Copy, Clone)]
+pub struct InfiniteOptions {
+    layers: u32,
+    scale_diff: f32, // Difference between each scale
+    filter: FilterType,
+    fade: bool,
+    easing: Easing,
+}
+
+impl From<&Command> for InfiniteOptions {
+    fn from(command: &Command) -> Self {
+        let layers = command.option_value("layers").map_or(5, |layers| layers.clamp(1.0, 10.0) as u32);
+        let spacing = command.option_value("spacing").map_or(100.0, |spacing| spacing.clamp(10.0, 200.0));
+        let filter = if command.has_option("smooth") { FilterType::Triangle } else { FilterType::Nearest };
+
+        Self {
+            layers,
+            scale_diff: spacing / 100.0,
+            filter,
+            fade: command.has_option("fade"),
+            easing: command.into(),
+        }
+    }
+}
+
+pub fn infinite(frames: &mut Vec<Frame>, speed: f32, direction: Direction, options: InfiniteOptions) {
     align_speed(frames, 8.0);
     let Some(frame) = frames.first() else { return; };
 
     let delay_centisecs = get_delay_centisecs(frame.delay());
     let centisecs_per_infinite = (100.0 * speed) / 8.0;
 
-    let scales_amount: u32 = 5;
-    let scale_diff: f32 = 1.0; // Difference between each scale
-    let scale_step = delay_centisecs / centisecs_per_infinite; // Scale shift between frames
-    let interval = (scale_diff / scale_step).floor() as usize;
+    let scale_step = (delay_centisecs / centisecs_per_infinite) * options.scale_diff; // Scale shift between frames
+    let interval = (options.scale_diff / scale_step).floor().max(1.0) as usize;
 
     *frames = align_gif(frames, interval);
 
-    let mut scales: Vec<f32> = Vec::new();
-    set_scales(&mut scales, scales_amount, scale_diff, scale_step);
+    for (index, frame) in frames.iter_mut().enumerate() {
+        // Shift over exactly one scale difference per interval so the loop is seamless
+        let progress = options.easing.apply((index % interval) as f32 / interval as f32);
+        let offset = match direction {
+            Direction::Outwards => progress * options.scale_diff,
+            Direction::Inwards => (1.0 - progress) * options.scale_diff,
+        };
 
-    for frame in frames {
-        infinite_shift_frame(&scales, frame);
-        shift_infinite_scales(&mut scales, scale_diff, scale_step);
+        let scales = get_scales(options, offset + scale_step);
+        infinite_shift_frame(&scales, frame, options);
     }
 }
 
-fn set_scales(scales: &mut Vec<f32>, scales_amount: u32, scale_diff: f32, scale_step: f32) {
-    scales.clear();
-
-    let new_scales = (0..scales_amount)
-        .map(|depth| ((scales_amount as f32) - (depth as f32) - 1.0) * scale_diff + scale_step);
-
-    scales.extend(new_scales);
-}
-
-fn shift_infinite_scales(scales: &mut Vec<f32>, scale_diff: f32, scale_step: f32) {
-    let scale = scales.first().copied().unwrap_or(0.0);
-
-    if scale >= (scales.len() as f32) * scale_diff {
-        set_scales(scales, scales.len() as u32, scale_diff, scale_step);
-    } else {
-        for scale in scales {
-            *scale += scale_step;
-        }
-    }
+fn get_scales(options: InfiniteOptions, offset: f32) -> Vec<f32> {
+    // Largest scale first so smaller layers are stacked on top
+    (0..options.layers)
+        .map(|depth| ((options.layers as f32) - (depth as f32) - 1.0) * options.scale_diff + offset)
+        .collect()
 }
 
-fn infinite_shift_frame(scales: &[f32], frame: &mut Frame) {
+fn infinite_shift_frame(scales: &[f32], frame: &mut Frame, options: InfiniteOptions) {
     let buffer_width = frame.buffer().width() as f32;
     let buffer_height = frame.buffer().height() as f32;
+    let max_scale = options.layers as f32 * options.scale_diff;
 
     let mut stacked_buffer = RgbaImage::new(
         buffer_width as u32,
@@ -61,18 +81,22 @@ fn infinite_shift_frame(scales: &[f32], frame: &mut Frame) {
     for &scale in scales.iter() {
         let scaled_width = (buffer_width * scale).round();
         let scaled_height = (buffer_height * scale).round();
+        if scaled_width < 1.0 || scaled_height < 1.0 { continue; }
 
-        let scaled_buffer = imageops::resize(
+        let mut scaled_buffer = imageops::resize(
             frame.buffer(),
             scaled_width as u32,
             scaled_height as u32,
-            FilterType::Nearest,
+            options.filter,
         );
 
-        let dx = ((scaled_width - buffer_width) / 2.0).round() as i64;
-        let dy = ((scaled_height - buffer_height) / 2.0).round() as i64;
+        if options.fade {
+            // Outer layers fade out as they approach the largest scale
+            let opacity = (1.0 - scale / max_scale).clamp(0.0, 1.0);
+            fade_buffer(&mut scaled_buffer, opacity);
+        }
 
-        imageops::overlay(&mut stacked_buffer, &scaled_buffer, -dx, -dy);
+        overlay_centered(&mut stacked_buffer, &scaled_buffer);
     }
 
     *frame.buffer_mut() = stacked_buffer;
@@ -0,0 +1,35 @@
+use std::f32::consts::PI;
+use image::{Frame, RgbaImage};
+
+use crate::{easing::get_progress, resize::scale_buffer, utils::{align_gif, align_speed, get_delay_centisecs, overlay_bottom}};
+
+const WOBBLES: f32 = 3.0;
+const DAMPING: f32 = 4.0;
+const STRENGTH: f32 = 0.25;
+
+pub fn jello(frames: &mut Vec<Frame>, speed: f32) {
+    align_speed(frames, 6.0);
+    let Some(frame) = frames.first() else { return };
+
+    let delay_centisecs = get_delay_centisecs(frame.delay());
+    let centisecs_per_jello = (120.0 * speed) / 8.0;
+    let jello_step = delay_centisecs / centisecs_per_jello;
+    let interval = (1.0 / jello_step).floor();
+
+    *frames = align_gif(frames, interval as usize);
+
+    for (index, frame) in frames.iter_mut().enumerate() {
+        let progress = get_progress(index, jello_step);
+
+        // Damped so the wobble has settled by the time the cycle loops
+        let wobble = (-DAMPING * progress).exp() * (2.0 * PI * WOBBLES * progress).sin();
+        let scale_x = 1.0 + STRENGTH * wobble;
+        let scale_y = 1.0 - STRENGTH * wobble;
+
+        let squished_buffer = scale_buffer(frame.buffer(), scale_x, scale_y);
+        let mut canvas = RgbaImage::new(frame.buffer().width(), frame.buffer().height());
+        overlay_bottom(&mut canvas, &squished_buffer);
+
+        *frame.buffer_mut() = canvas;
+    }
+}
@@ -1,31 +1,72 @@
 extern crate console_error_panic_hook;
 
-use std::mem;
+use backdrop::backdrop;
+use bounce::bounce;
+use canvas::{crop, pad, square, trim};
+use chroma::{chroma, remove_background};
+use color::{color_filter, ColorFilter};
 use command::Command;
+use compose::{compose, Input};
 use image::{codecs::gif::{GifEncoder, Repeat}, Frame};
+use glitch::glitch;
 use infinite::infinite;
-use rain::rain;
+use jello::jello;
+use mask::{mask, mask_image};
+use outline::outline;
+use particles::{particles, Preset};
+use pet::pet;
+use pulse::pulse;
 use rainbow::rainbow;
 use resize::resize;
+use rock::rock;
 use rotate::rotate;
+use deepfry::deepfry;
+use fade::fade;
+use filter::{blur, pixelate, sharpen};
 use flip::flip;
 use shake::shake;
 use slide::slide;
+use speed::{speed, speed_multiply, hyperspeed};
 use spin::spin;
-use utils::{get_frames_and_scale, get_delay};
+use sticker::sticker;
+use text::text;
+use trail::{merge_samples, supersample, trail, Trail};
+use utils::{dither_alpha, get_frames, get_frames_and_scale, get_scale};
 use wasm_bindgen::{prelude::wasm_bindgen, JsValue, JsError};
 use wiggle::wiggle;
 
+mod alpha;
+mod backdrop;
+mod bounce;
+mod canvas;
+mod chroma;
+mod color;
 mod command;
+mod compose;
+mod easing;
+mod deepfry;
+mod fade;
+mod filter;
 mod flip;
-mod rain;
+mod jello;
+mod mask;
+mod outline;
+mod particles;
+mod pet;
+mod pulse;
 mod rainbow;
 mod resize;
+mod rock;
 mod rotate;
 mod spin;
+mod sticker;
+mod text;
+mod trail;
+mod glitch;
 mod infinite;
 mod utils;
 mod slide;
+mod speed;
 mod wiggle;
 mod shake;
 
@@ -46,81 +87,171 @@ pub fn apply_commands(data: Vec<u8>, format_type: String, commands: JsValue) ->
 
     let (mut frames, scale) = get_frames_and_scale(&data, &format_type, &mut commands)?;
     if frames.is_empty() { return Ok(data); }
-    let overall_size = scale.0 * scale.1;
 
-    let mut output = Vec::new();
-    {
-        let mut writer = GifEncoder::new_with_speed(&mut output, 10);
-        writer.set_repeat(Repeat::Infinite)?;
+    let repeat = render(&mut frames, scale, &commands)?;
+    encode(frames, repeat)
+}
 
-        if overall_size < 1.0  {
-            resize(&mut frames, scale);
-        }
+#[wasm_bindgen(js_name = "composeEmotes")]
+pub fn compose_emotes(inputs: JsValue, layout: JsValue) -> Result<Vec<u8>, JsError> {
+    let inputs: Vec<Input> = serde_wasm_bindgen::from_value(inputs)?;
+    let layout: Command = serde_wasm_bindgen::from_value(layout)?;
 
-        for command in &commands {
-            let name = command.name.as_str();
-            match name {
-                "speed" => speed(&mut frames, command.param),
-                "hyperspeed" => hyperspeed(&mut frames),
-                "reverse" => reverse(&mut frames),
-                "flip" => flip(&mut frames, command.param),
-                "rain" => rain(&mut frames, command.param),
-                "rainbow" => rainbow(&mut frames, command.param),
-                "rotate" => rotate(&mut frames, command.param),
-                "spin" => spin(&mut frames, command.param, spin::Direction::Clockwise),
-                "spinrev" => spin(&mut frames, command.param, spin::Direction::CounterClockwise),
-                "infinite" => infinite(&mut frames, command.param),
-                "slide" => slide(&mut frames, command.param, slide::Direction::Forwards),
-                "sliderev" => slide(&mut frames, command.param, slide::Direction::Backwards),
-                "wiggle" => wiggle(&mut frames, command.param),
-                "shake" => shake(&mut frames, command.param),
-                _ => {},
-            };
-        }
+    let mut layers = Vec::with_capacity(inputs.len());
+    for mut input in inputs {
+        let scale = get_scale(&mut input.commands);
+        let mut frames = get_frames(&input.data, &input.format_type)?;
 
-        if overall_size > 1.0 {
-            resize(&mut frames, scale);
-        }
+        // Each emote loops on its own timeline, so one-shot repeats don't apply here
+        render(&mut frames, scale, &input.commands)?;
+        layers.push(frames);
+    }
 
-        for frame in frames {
-            writer.encode_frame(frame)?;
+    let frames = compose(layers, (&layout).into());
+    encode(frames, Repeat::Infinite)
+}
+
+fn render(frames: &mut Vec<Frame>, scale: (f32, f32), commands: &[Command]) -> Result<Repeat, JsError> {
+    let overall_size = scale.0 * scale.1;
+    let mut repeat = Repeat::Infinite;
+
+    if overall_size < 1.0  {
+        resize(frames, scale);
+    }
+
+    for command in commands {
+        let name = command.name.as_str();
+        let motion_trail = Trail::from_options(command);
+        if let Some(Trail::Blur(samples)) = motion_trail {
+            supersample(frames, samples);
         }
-    };
 
-    Ok(output)
-}
+        match name {
+            "speed" => speed(frames, command.param),
+            "speedmultiply" => speed_multiply(frames, command.param),
+            "hyperspeed" => hyperspeed(frames, command.param),
+            "reverse" => reverse(frames),
+            "boomerang" => boomerang(frames),
+            "flip" => flip(frames, command.param),
+            "rain" => {
+                let preset = if command.has_option("glitter") { Preset::Glitter } else { Preset::Rain };
+                particles(frames, command.param, preset, command.into());
+            },
+            "confetti" => particles(frames, command.param, Preset::Confetti, command.into()),
+            "hearts" => particles(frames, command.param, Preset::Hearts, command.into()),
+            "sparkle" => particles(frames, command.param, Preset::Sparkle, command.into()),
+            "fire" => particles(frames, command.param, Preset::Fire, command.into()),
+            "bubbles" => particles(frames, command.param, Preset::Bubbles, command.into()),
+            "rainbow" => rainbow(frames, command.param),
+            "rotate" => rotate(frames, command.param),
+            "spin" => spin(frames, command.param, spin::Direction::Clockwise, command.into()),
+            "spinrev" => spin(frames, command.param, spin::Direction::CounterClockwise, command.into()),
+            "infinite" => infinite(frames, command.param, infinite::Direction::Outwards, command.into()),
+            "infiniterev" => infinite(frames, command.param, infinite::Direction::Inwards, command.into()),
+            "slide" => slide(frames, command.param, slide::Direction::Forwards, command.into(), command.into()),
+            "sliderev" => slide(frames, command.param, slide::Direction::Backwards, command.into(), command.into()),
+            "slideup" => slide(frames, command.param, slide::Direction::Up, command.into(), command.into()),
+            "slidedown" => slide(frames, command.param, slide::Direction::Down, command.into(), command.into()),
+            "slidediag" => slide(frames, command.param, slide::Direction::ForwardsDown, command.into(), command.into()),
+            "slidediagrev" => slide(frames, command.param, slide::Direction::BackwardsUp, command.into(), command.into()),
+            "slideantidiag" => slide(frames, command.param, slide::Direction::ForwardsUp, command.into(), command.into()),
+            "slideantidiagrev" => slide(frames, command.param, slide::Direction::BackwardsDown, command.into(), command.into()),
+            "wiggle" => wiggle(frames, command.param, command.into()),
+            "shake" => shake(frames, command.param, command.into()),
+            "bounce" => bounce(frames, command.param, command.into()),
+            "pulse" => pulse(frames, command.param, pulse::Rhythm::Smooth, command.into()),
+            "heartbeat" => pulse(frames, command.param, pulse::Rhythm::Heartbeat, command.into()),
+            "rock" => rock(frames, command.param, command.into()),
+            "jello" => jello(frames, command.param),
+            "pet" => pet(frames, command.param)?,
+            "grayscale" => color_filter(frames, ColorFilter::Grayscale),
+            "sepia" => color_filter(frames, ColorFilter::Sepia),
+            "invert" => color_filter(frames, ColorFilter::Invert),
+            "tint" => {
+                if let Some(color) = command.color_option() {
+                    color_filter(frames, ColorFilter::Tint(color));
+                }
+            },
+            "saturation" => color_filter(frames, ColorFilter::Saturation(command.param / 100.0)),
+            "brightness" => color_filter(frames, ColorFilter::Brightness(command.param / 100.0)),
+            "contrast" => color_filter(frames, ColorFilter::Contrast(command.param / 100.0)),
+            "hue" => color_filter(frames, ColorFilter::HueRotate(command.param)),
+            "deepfry" => deepfry(frames, command.param),
+            "pixelate" => pixelate(frames, command.param, command.into()),
+            "blur" => blur(frames, command.param),
+            "sharpen" => sharpen(frames, command.param / 100.0),
+            "glitch" => glitch(frames, command.param, command.into()),
+            "outline" => outline(frames, outline::Outline::Stroke, command.color_option(), command.param),
+            "glow" => outline(frames, outline::Outline::Glow, command.color_option(), command.param),
+            "shadow" => outline(frames, outline::Outline::Shadow, command.color_option(), command.param),
+            "crop" => crop(frames, command.into()),
+            "pad" => pad(frames, command.param, command.color_option()),
+            "trim" => trim(frames),
+            "square" => square(frames, command.color_option()),
+            "bg" => backdrop(frames, command.into()),
+            "chroma" => chroma(frames, command.color_option(), command.param),
+            "removebg" => remove_background(frames, command.param),
+            "mask" => match &command.data {
+                Some(data) => mask_image(frames, data)?,
+                None => mask(frames, command.into()),
+            },
+            "text" => {
+                if let Some(content) = &command.text {
+                    text(frames, command.param, content, command.into())?;
+                }
+            },
+            "sticker" => sticker(frames, command.param, command.into())?,
+            "trail" => trail(frames, command.param as u32),
+            "fade" => fade(frames, command.param, fade::Fade::Loop),
+            "blink" => fade(frames, command.param, fade::Fade::Blink),
+            "fadein" | "fadeout" => {
+                let direction = if name == "fadein" { fade::Fade::In } else { fade::Fade::Out };
+                fade(frames, command.param, direction);
 
-fn speed(frames: &mut [Frame], value: f32) {
-    for frame in frames {
-        set_speed(frame, value as u32);
+                // One-shot fades would restart every loop, so only play once
+                repeat = Repeat::Finite(0);
+            },
+            _ => {},
+        };
+
+        match motion_trail {
+            Some(Trail::Ghosts(ghosts)) => trail(frames, ghosts),
+            Some(Trail::Blur(samples)) => merge_samples(frames, samples),
+            None => {},
+        }
     }
-}
 
-fn set_speed(frame: &mut Frame, speed: u32) {
-    let left = frame.left();
-    let top = frame.top();
+    if overall_size > 1.0 {
+        resize(frames, scale);
+    }
 
-    *frame = Frame::from_parts(
-        mem::take(frame.buffer_mut()),
-        left,
-        top,
-        get_delay(speed),
-    );
+    Ok(repeat)
 }
 
-fn hyperspeed(frames: &mut Vec<Frame>) {
-    if frames.len() <= 4 { return speed(frames, 2.0); }
+fn encode(frames: Vec<Frame>, repeat: Repeat) -> Result<Vec<u8>, JsError> {
+    let mut output = Vec::new();
+    {
+        let mut writer = GifEncoder::new_with_speed(&mut output, 10);
 
-    let mut index = 0;
-    frames.retain_mut(|frame| {
-        let retain = index % 2 == 0;
-        if retain { set_speed(frame, 2); }
+        writer.set_repeat(repeat)?;
+        for mut frame in frames {
+            // Effects keep partial transparency so they blend with each other, GIFs can only show it dithered
+            dither_alpha(frame.buffer_mut());
+            writer.encode_frame(frame)?;
+        }
+    };
 
-        index += 1;
-        retain
-    });
+    Ok(output)
 }
 
 fn reverse(frames: &mut [Frame]) {
     frames.reverse();
 }
+
+fn boomerang(frames: &mut Vec<Frame>) {
+    if frames.len() < 3 { return; }
+
+    // Leave out both end frames on the way back so they aren't shown twice in a row when looping
+    let returning: Vec<Frame> = frames[1..frames.len() - 1].iter().rev().cloned().collect();
+    frames.extend(returning);
+}
@@ -0,0 +1,140 @@
+use std::f32::consts::PI;
+use image::{imageops::{self, FilterType}, Frame, GrayImage, Luma, RgbaImage};
+use wasm_bindgen::JsError;
+
+use crate::command::Command;
+
+// Samples per pixel in each direction, used to anti-alias the edges
+const SUPERSAMPLING: u32 = 4;
+
+#[derive(Copy, Clone)]
+pub enum Shape {
+    Circle,
+    Rounded(f32),
+    Heart,
+    Star,
+}
+
+impl From<&Command> for Shape {
+    fn from(command: &Command) -> Self {
+        let radius = command.options.iter().find_map(|option| option.parse().ok());
+
+        if command.has_option("rounded") {
+            Shape::Rounded(radius.unwrap_or(20.0))
+        } else if command.has_option("heart") {
+            Shape::Heart
+        } else if command.has_option("star") {
+            Shape::Star
+        } else {
+            Shape::Circle
+        }
+    }
+}
+
+impl Shape {
+    fn contains(self, x: f32, y: f32, width: f32, height: f32) -> bool {
+        match self {
+            Shape::Circle => {
+                let u = x / width - 0.5;
+                let v = y / height - 0.5;
+                u * u + v * v <= 0.25
+            },
+            Shape::Rounded(radius) => {
+                let radius = width.min(height) * radius.clamp(0.0, 50.0) / 100.0;
+                let dx = (radius - x).max(x - (width - radius)).max(0.0);
+                let dy = (radius - y).max(y - (height - radius)).max(0.0);
+                dx * dx + dy * dy <= radius * radius
+            },
+            Shape::Heart => {
+                // Implicit heart curve, flipped so it points down
+                let u = (x / width - 0.5) * 2.5;
+                let v = (0.55 - y / height) * 2.5;
+                (u * u + v * v - 1.0).powi(3) - u * u * v.powi(3) <= 0.0
+            },
+            Shape::Star => is_inside_star(x / width, y / height),
+        }
+    }
+}
+
+pub fn mask(frames: &mut [Frame], shape: Shape) {
+    let Some(frame) = frames.first() else { return };
+    let width = frame.buffer().width();
+    let height = frame.buffer().height();
+
+    let coverage = GrayImage::from_fn(width, height, |x, y| {
+        let mut inside = 0;
+
+        for sample_y in 0..SUPERSAMPLING {
+            for sample_x in 0..SUPERSAMPLING {
+                let offset_x = (sample_x as f32 + 0.5) / SUPERSAMPLING as f32;
+                let offset_y = (sample_y as f32 + 0.5) / SUPERSAMPLING as f32;
+
+                if shape.contains(x as f32 + offset_x, y as f32 + offset_y, width as f32, height as f32) {
+                    inside += 1;
+                }
+            }
+        }
+
+        Luma([(inside * 255 / (SUPERSAMPLING * SUPERSAMPLING)) as u8])
+    });
+
+    apply_mask(frames, &coverage);
+}
+
+pub fn mask_image(frames: &mut [Frame], data: &[u8]) -> Result<(), JsError> {
+    let Some(frame) = frames.first() else { return Ok(()) };
+    let width = frame.buffer().width();
+    let height = frame.buffer().height();
+
+    let mask_image = image::load_from_memory(data)?.into_rgba8();
+    let mask_image = imageops::resize(&mask_image, width, height, FilterType::Triangle);
+
+    // Transparent masks are cut out by their shape, opaque ones by their brightness
+    let has_transparency = mask_image.pixels().any(|pixel| pixel.0[3] < 255);
+    let coverage = GrayImage::from_fn(width, height, |x, y| {
+        let [red, green, blue, alpha] = mask_image.get_pixel(x, y).0;
+        if has_transparency { return Luma([alpha]); }
+
+        let luma = red as f32 * 0.299 + green as f32 * 0.587 + blue as f32 * 0.114;
+        Luma([luma.round() as u8])
+    });
+
+    apply_mask(frames, &coverage);
+    Ok(())
+}
+
+fn apply_mask(frames: &mut [Frame], coverage: &GrayImage) {
+    for frame in frames {
+        let buffer: &mut RgbaImage = frame.buffer_mut();
+
+        for (pixel, coverage) in buffer.pixels_mut().zip(coverage.pixels()) {
+            pixel.0[3] = (pixel.0[3] as u32 * coverage.0[0] as u32 / 255) as u8;
+        }
+    }
+}
+
+fn is_inside_star(u: f32, v: f32) -> bool {
+    let points: Vec<(f32, f32)> = (0..10)
+        .map(|index| {
+            // Alternate between outer and inner points, starting at the top
+            let radius = if index % 2 == 0 { 0.5 } else { 0.2 };
+            let angle = index as f32 * PI / 5.0 - PI / 2.0;
+
+            (0.5 + radius * angle.cos(), 0.53 + radius * angle.sin())
+        })
+        .collect();
+
+    let mut inside = false;
+    let mut previous = points[points.len() - 1];
+
+    for &point in &points {
+        let crosses = (point.1 > v) != (previous.1 > v);
+        if crosses && u < (previous.0 - point.0) * (v - point.1) / (previous.1 - point.1) + point.0 {
+            inside = !inside;
+        }
+
+        previous = point;
+    }
+
+    inside
+}
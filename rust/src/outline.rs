@@ -0,0 +1,54 @@
+use image::{imageops, Frame, Rgba, RgbaImage};
+
+use crate::{
+    alpha::{blur_mask, colorize_mask, dilate_mask, get_alpha_mask},
+    canvas::expand,
+};
+
+#[derive(Copy, Clone)]
+pub enum Outline {
+    Stroke,
+    Glow,
+    Shadow,
+}
+
+impl Outline {
+    fn default_color(self) -> Rgba<u8> {
+        match self {
+            Outline::Stroke | Outline::Glow => Rgba([255, 255, 255, 255]),
+            Outline::Shadow => Rgba([0, 0, 0, 255]),
+        }
+    }
+}
+
+pub fn outline(frames: &mut [Frame], outline: Outline, color: Option<Rgba<u8>>, amount: f32) {
+    let Some(frame) = frames.first() else { return };
+    let size = frame.buffer().width().max(frame.buffer().height()) as f32;
+
+    let color = color.unwrap_or_else(|| outline.default_color());
+    // Sizes are relative to a 48px emote unless given
+    let amount = if amount > 0.0 { amount.min(size / 4.0) } else { (size / 48.0).max(1.0) * 2.0 };
+
+    // Grow the canvas so nothing gets cut off
+    let (radius, sigma, offset, margin) = match outline {
+        Outline::Stroke => (amount, 0.0, 0, amount.ceil() as u32 + 1),
+        Outline::Glow => (amount / 2.0, amount / 2.0, 0, (amount * 2.0).ceil() as u32),
+        Outline::Shadow => (0.0, amount / 3.0, amount.round() as i64, (amount * 2.0).ceil() as u32),
+    };
+
+    expand(frames, margin);
+
+    for frame in frames {
+        let mask = get_alpha_mask(frame.buffer());
+        let grown_mask = if radius > 0.0 { dilate_mask(&mask, radius) } else { mask };
+        let opacity = if let Outline::Shadow = outline { 0.6 } else { 1.0 };
+
+        let layer = colorize_mask(&blur_mask(&grown_mask, sigma), color, opacity);
+
+        let mut canvas = RgbaImage::new(layer.width(), layer.height());
+        imageops::overlay(&mut canvas, &layer, offset, offset);
+        imageops::overlay(&mut canvas, frame.buffer(), 0, 0);
+
+        *frame.buffer_mut() = canvas;
+    }
+}
@@ -0,0 +1,380 @@
+use std::f32::consts::PI;
+use image::{Frame, Rgba, RgbaImage};
+
+use crate::{command::Command, easing::get_progress, utils::{align_gif, align_speed, get_delay_centisecs, get_random_u32, SeededRng}};
+
+const RAIN: &[[u8; 3]] = &[[0, 120, 255]];
+const CONFETTI: &[[u8; 3]] = &[[255, 70, 90], [255, 200, 40], [60, 200, 120], [60, 150, 255], [190, 90, 255], [255, 140, 40]];
+const HEARTS: &[[u8; 3]] = &[[255, 60, 110], [255, 110, 160], [230, 30, 70]];
+const SPARKLE: &[[u8; 3]] = &[[255, 250, 200], [255, 230, 120], [255, 255, 255]];
+const BUBBLES: &[[u8; 3]] = &[[170, 220, 255], [200, 240, 255]];
+
+#[derive(Copy, Clone)]
+pub enum Preset {
+    Rain,
+    Glitter,
+    Confetti,
+    Hearts,
+    Sparkle,
+    Fire,
+    Bubbles,
+}
+
+#[derive(Copy, Clone)]
+pub enum Emitter {
+    Top,
+    Bottom,
+    // Bursts out of the center and falls down
+    Burst,
+    // Appears in place anywhere on the emote
+    Anywhere,
+}
+
+impl Emitter {
+    fn from_options(command: &Command) -> Option<Self> {
+        command.options.iter().find_map(|option| match option.as_str() {
+            "top" => Some(Emitter::Top),
+            "bottom" => Some(Emitter::Bottom),
+            "burst" => Some(Emitter::Burst),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Copy, Clone)]
+enum Shape {
+    Streak,
+    Dot,
+    Confetti,
+    Heart,
+    Sparkle,
+    Bubble,
+}
+
+#[derive(Copy, Clone)]
+enum Palette {
+    Fixed(&'static [[u8; 3]]),
+    Random,
+    // Cools down from yellow to dark red over the lifetime
+    Heat,
+}
+
+struct Style {
+    emitter: Emitter,
+    shape: Shape,
+    palette: Palette,
+    // Particles per 100 pixels of width plus height
+    amount: f32,
+    // Length of the loop in centiseconds at normal speed
+    duration: f32,
+    // Emote sizes travelled per loop
+    speed: (f32, f32),
+    gravity: f32,
+    // Side to side movement in emote sizes
+    sway: f32,
+    // Part of the loop a particle is alive for, it also dies when it leaves the emote
+    lifetime: (f32, f32),
+    // Size in pixels on a 48px emote
+    size: (f32, f32),
+}
+
+impl Preset {
+    fn style(self) -> Style {
+        match self {
+            Preset::Rain | Preset::Glitter => Style {
+                emitter: Emitter::Top,
+                shape: Shape::Streak,
+                palette: if let Preset::Rain = self { Palette::Fixed(RAIN) } else { Palette::Random },
+                amount: 20.0,
+                duration: 100.0,
+                speed: (1.5, 3.0),
+                gravity: 0.0,
+                sway: 0.0,
+                lifetime: (1.0, 1.0),
+                size: (1.0, 5.0),
+            },
+            Preset::Confetti => Style {
+                emitter: Emitter::Top,
+                shape: Shape::Confetti,
+                palette: Palette::Fixed(CONFETTI),
+                amount: 24.0,
+                duration: 200.0,
+                speed: (1.1, 1.5),
+                gravity: 0.0,
+                sway: 0.04,
+                lifetime: (1.0, 1.0),
+                size: (2.0, 4.0),
+            },
+            Preset::Hearts => Style {
+                emitter: Emitter::Bottom,
+                shape: Shape::Heart,
+                palette: Palette::Fixed(HEARTS),
+                amount: 8.0,
+                duration: 200.0,
+                speed: (1.1, 1.4),
+                gravity: 0.0,
+                sway: 0.05,
+                lifetime: (1.0, 1.0),
+                size: (5.0, 9.0),
+            },
+            Preset::Sparkle => Style {
+                emitter: Emitter::Anywhere,
+                shape: Shape::Sparkle,
+                palette: Palette::Fixed(SPARKLE),
+                amount: 10.0,
+                duration: 150.0,
+                speed: (0.0, 0.0),
+                gravity: 0.0,
+                sway: 0.0,
+                lifetime: (0.2, 0.4),
+                size: (6.0, 12.0),
+            },
+            Preset::Fire => Style {
+                emitter: Emitter::Bottom,
+                shape: Shape::Dot,
+                palette: Palette::Heat,
+                amount: 60.0,
+                duration: 100.0,
+                speed: (1.0, 1.6),
+                gravity: 0.0,
+                sway: 0.02,
+                lifetime: (0.3, 0.6),
+                size: (3.0, 6.0),
+            },
+            Preset::Bubbles => Style {
+                emitter: Emitter::Bottom,
+                shape: Shape::Bubble,
+                palette: Palette::Fixed(BUBBLES),
+                amount: 8.0,
+                duration: 300.0,
+                speed: (1.1, 1.3),
+                gravity: 0.0,
+                sway: 0.03,
+                lifetime: (1.0, 1.0),
+                size: (4.0, 9.0),
+            },
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct ParticleOptions {
+    emitter: Option<Emitter>,
+    seed: Option<u64>,
+}
+
+impl From<&Command> for ParticleOptions {
+    fn from(command: &Command) -> Self {
+        Self {
+            emitter: Emitter::from_options(command),
+            seed: command.option_value("seed").map(|seed| seed.abs() as u64),
+        }
+    }
+}
+
+struct Particle {
+    // Starting point and velocity, in emote sizes and loops
+    x: f32,
+    y: f32,
+    velocity_x: f32,
+    velocity_y: f32,
+    // When in the loop the particle appears, and for how long
+    spawn: f32,
+    lifetime: f32,
+    size: f32,
+    color: [u8; 3],
+    phase: f32,
+}
+
+pub fn particles(frames: &mut Vec<Frame>, speed: f32, preset: Preset, options: ParticleOptions) {
+    align_speed(frames, 6.0);
+    let Some(frame) = frames.first() else { return };
+    let width = frame.buffer().width();
+    let height = frame.buffer().height();
+
+    let mut style = preset.style();
+    if let Some(emitter) = options.emitter {
+        style.emitter = emitter;
+    }
+    if let Emitter::Burst = style.emitter {
+        style.gravity = style.gravity.max(1.5);
+    }
+
+    let delay_centisecs = get_delay_centisecs(frame.delay());
+    let centisecs_per_loop = (style.duration * speed) / 8.0;
+    let step = delay_centisecs / centisecs_per_loop;
+    let interval = (1.0 / step).floor().max(1.0) as usize;
+
+    *frames = align_gif(frames, interval);
+
+    let seed = options.seed.unwrap_or_else(|| get_random_u32(0, u32::MAX) as u64);
+    let particles = create_particles(&style, width, height, &mut SeededRng::new(seed));
+
+    for (index, frame) in frames.iter_mut().enumerate() {
+        // Every particle follows the same path each loop, so the end lines up with the start
+        let time = get_progress(index, step);
+        let buffer = frame.buffer_mut();
+
+        for particle in &particles {
+            let age = (time - particle.spawn).rem_euclid(1.0);
+            if age >= particle.lifetime { continue; }
+
+            draw_particle(buffer, &style, particle, age);
+        }
+    }
+}
+
+fn create_particles(style: &Style, width: u32, height: u32, rng: &mut SeededRng) -> Vec<Particle> {
+    let amount = ((width + height) as f32 / 100.0 * style.amount).round().max(1.0) as usize;
+    let scale = width.max(height) as f32 / 48.0;
+    let mut between = |(min, max): (f32, f32)| min + rng.next_f32() * (max - min);
+
+    (0..amount)
+        .map(|_| {
+            let size = (between(style.size) * scale).max(1.0);
+            // Particles that live for the whole loop need to get across the emote within it
+            let speed = match style.emitter {
+                Emitter::Top | Emitter::Bottom if style.lifetime.1 >= 1.0 => between(style.speed).max(1.1),
+                Emitter::Top | Emitter::Bottom => between(style.speed).max(0.1),
+                _ => between(style.speed),
+            };
+            // Start just outside the emote so particles don't pop in
+            let margin_x = size / width as f32;
+            let margin_y = size / height as f32;
+
+            let (x, y, velocity_x, velocity_y) = match style.emitter {
+                Emitter::Top => (between((0.0, 1.0)), -margin_y, 0.0, speed),
+                Emitter::Bottom => (between((0.0, 1.0)), 1.0 + margin_y, 0.0, -speed),
+                Emitter::Burst => {
+                    let angle = between((0.0, 2.0 * PI));
+                    let speed = between((0.6, 1.2));
+                    (0.5, 0.5, angle.cos() * speed, angle.sin() * speed - 0.6)
+                },
+                Emitter::Anywhere => (between((margin_x, 1.0 - margin_x)), between((margin_y, 1.0 - margin_y)), 0.0, 0.0),
+            };
+
+            // Particles moving in a straight line die once they have crossed the emote
+            let crossing = match style.emitter {
+                Emitter::Top | Emitter::Bottom => (1.0 + margin_y * 2.0) / speed,
+                Emitter::Burst | Emitter::Anywhere => 1.0,
+            };
+
+            let color = match style.palette {
+                Palette::Fixed(colors) => colors[(between((0.0, colors.len() as f32)) as usize).min(colors.len() - 1)],
+                Palette::Random => [0; 3].map(|_: u8| between((0.0, 255.0)).round() as u8),
+                Palette::Heat => [255, 230, 80],
+            };
+
+            Particle {
+                x,
+                y,
+                velocity_x,
+                velocity_y,
+                spawn: between((0.0, 1.0)),
+                lifetime: between(style.lifetime).min(crossing).min(1.0),
+                size,
+                color,
+                phase: between((0.0, 2.0 * PI)),
+            }
+        })
+        .collect()
+}
+
+fn draw_particle(buffer: &mut RgbaImage, style: &Style, particle: &Particle, age: f32) {
+    let width = buffer.width() as f32;
+    let height = buffer.height() as f32;
+    let life = age / particle.lifetime;
+
+    let sway = (2.0 * PI * age * 2.0 + particle.phase).sin() * style.sway;
+    let x = (particle.x + particle.velocity_x * age + sway) * width;
+    let y = (particle.y + particle.velocity_y * age + 0.5 * style.gravity * age * age) * height;
+
+    let (size, color) = match (style.shape, style.palette) {
+        // Flames shrink and cool down as they rise
+        (_, Palette::Heat) => (particle.size * (1.0 - life * 0.7), heat_color(life)),
+        // Sparkles grow and shrink again
+        (Shape::Sparkle, _) => (particle.size * (PI * life).sin(), particle.color),
+        _ => (particle.size, particle.color),
+    };
+    let pixel = Rgba([color[0], color[1], color[2], 255]);
+
+    match style.shape {
+        Shape::Streak => {
+            // Rain drops keep their length, but are only one or two pixels wide
+            let thickness = (particle.size / 3.0).clamp(1.0, 2.0);
+            fill_rect(buffer, x, y - size / 2.0, thickness, size, pixel);
+        },
+        Shape::Dot => fill_shape(buffer, x, y, size, pixel, |u, v| u * u + v * v <= 1.0),
+        Shape::Confetti => {
+            // Flip over by squashing the width
+            let flip = (2.0 * PI * age * 3.0 + particle.phase).cos().abs().max(0.2);
+            fill_rect(buffer, x - size * flip / 2.0, y - size / 4.0, size * flip, size / 2.0, pixel);
+        },
+        Shape::Heart => fill_shape(buffer, x, y, size, pixel, |u, v| {
+            let (u, v) = (u * 1.2, -v * 1.2 + 0.2);
+            (u * u + v * v - 1.0).powi(3) - u * u * v.powi(3) <= 0.0
+        }),
+        Shape::Sparkle => fill_shape(buffer, x, y, size, pixel, |u, v| {
+            let (u, v) = (u.abs(), v.abs());
+            u.powf(0.7) + v.powf(0.7) <= 1.0
+        }),
+        Shape::Bubble => {
+            let rim = (1.0 - 1.5 / size.max(1.5)).max(0.0);
+            fill_shape(buffer, x, y, size, pixel, |u, v| {
+                let distance = u * u + v * v;
+                // Outline with a small highlight in the top left
+                (distance <= 1.0 && distance >= rim * rim) || ((u + 0.4).powi(2) + (v + 0.4).powi(2) <= 0.05)
+            });
+        },
+    }
+}
+
+fn heat_color(life: f32) -> [u8; 3] {
+    const COLORS: [[f32; 3]; 4] = [[255.0, 230.0, 80.0], [255.0, 140.0, 0.0], [220.0, 40.0, 20.0], [120.0, 20.0, 10.0]];
+
+    let position = life.clamp(0.0, 1.0) * (COLORS.len() - 1) as f32;
+    let index = (position as usize).min(COLORS.len() - 2);
+    let amount = position - index as f32;
+
+    [0, 1, 2].map(|channel| {
+        let value = COLORS[index][channel] + (COLORS[index + 1][channel] - COLORS[index][channel]) * amount;
+        value.round() as u8
+    })
+}
+
+fn fill_rect(buffer: &mut RgbaImage, x: f32, y: f32, width: f32, height: f32, pixel: Rgba<u8>) {
+    let left = x.round().max(0.0) as u32;
+    let top = y.round().max(0.0) as u32;
+    let right = ((x + width).round().max(x.round() + 1.0).max(0.0) as u32).min(buffer.width());
+    let bottom = ((y + height).round().max(y.round() + 1.0).max(0.0) as u32).min(buffer.height());
+
+    for pixel_y in top..bottom {
+        for pixel_x in left..right {
+            buffer.put_pixel(pixel_x, pixel_y, pixel);
+        }
+    }
+}
+
+fn fill_shape(buffer: &mut RgbaImage, x: f32, y: f32, size: f32, pixel: Rgba<u8>, inside: impl Fn(f32, f32) -> bool) {
+    if size < 1.0 {
+        return fill_rect(buffer, x - 0.5, y - 0.5, 1.0, 1.0, pixel);
+    }
+
+    // Test the center of every pixel in the bounding box, in coordinates from -1 to 1
+    let radius = size / 2.0;
+    let left = (x - radius).floor().max(0.0) as u32;
+    let top = (y - radius).floor().max(0.0) as u32;
+    let right = ((x + radius).ceil().max(0.0) as u32).min(buffer.width());
+    let bottom = ((y + radius).ceil().max(0.0) as u32).min(buffer.height());
+
+    for pixel_y in top..bottom {
+        for pixel_x in left..right {
+            let u = (pixel_x as f32 + 0.5 - x) / radius;
+            let v = (pixel_y as f32 + 0.5 - y) / radius;
+
+            if inside(u, v) {
+                buffer.put_pixel(pixel_x, pixel_y, pixel);
+            }
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use image::{imageops, Frame, GenericImageView, ImageFormat, RgbaImage};
+use wasm_bindgen::JsError;
+
+use crate::{easing::get_progress, resize::scale_buffer, utils::{align_gif, align_speed, get_delay_centisecs, overlay_bottom}};
+
+// Sprite sheet with every hand frame next to each other
+const HAND_SPRITES: &[u8] = include_bytes!("../assets/pet_hand.png");
+const HAND_FRAMES: u32 = 5;
+// How much the emote is squashed down during each hand frame
+const SQUASH: [f32; HAND_FRAMES as usize] = [0.0, 0.1, 0.2, 0.12, 0.02];
+
+// Emote is shrunk to leave room for the hand above it
+const EMOTE_SCALE: f32 = 0.8;
+const HAND_SCALE: f32 = 0.7;
+// Height of the fingertips within a hand frame
+const FINGERTIPS: f32 = 0.6;
+
+pub fn pet(frames: &mut Vec<Frame>, speed: f32) -> Result<(), JsError> {
+    align_speed(frames, 4.0);
+    let Some(frame) = frames.first() else { return Ok(()) };
+    let width = frame.buffer().width();
+    let height = frame.buffer().height();
+
+    let delay_centisecs = get_delay_centisecs(frame.delay());
+    let centisecs_per_pat = (50.0 * speed) / 8.0;
+    let pat_step = delay_centisecs / centisecs_per_pat;
+    let interval = (1.0 / pat_step).floor();
+
+    let hands = get_hands(width, height)?;
+    *frames = align_gif(frames, interval as usize);
+
+    for (index, frame) in frames.iter_mut().enumerate() {
+        let progress = get_progress(index, pat_step);
+        let hand_index = ((progress * HAND_FRAMES as f32) as usize).min(HAND_FRAMES as usize - 1);
+        let squash = SQUASH[hand_index];
+
+        let squashed_buffer = scale_buffer(
+            frame.buffer(),
+            EMOTE_SCALE * (1.0 + squash / 2.0),
+            EMOTE_SCALE * (1.0 - squash),
+        );
+
+        let mut canvas = RgbaImage::new(width, height);
+        overlay_bottom(&mut canvas, &squashed_buffer);
+
+        // Rest the fingertips on top of the unsquashed emote, the sprites themselves move down
+        let hand = &hands[hand_index];
+        let hand_x = (width as i64 - hand.width() as i64) / 2;
+        let emote_top = height as f32 * (1.0 - EMOTE_SCALE);
+        let hand_y = (emote_top - hand.height() as f32 * FINGERTIPS).round() as i64;
+        imageops::overlay(&mut canvas, hand, hand_x, hand_y);
+
+        *frame.buffer_mut() = canvas;
+    }
+
+    Ok(())
+}
+
+fn get_hands(width: u32, height: u32) -> Result<Vec<RgbaImage>, JsError> {
+    let sprites = image::load_from_memory_with_format(HAND_SPRITES, ImageFormat::Png)?.into_rgba8();
+    let sprite_width = sprites.width() / HAND_FRAMES;
+    let sprite_height = sprites.height();
+
+    let hand_width = (width as f32 * HAND_SCALE).round().max(1.0) as u32;
+    let hand_height = (height as f32 * HAND_SCALE).round().max(1.0) as u32;
+
+    let hands = (0..HAND_FRAMES)
+        .map(|index| {
+            let sprite = sprites.view(index * sprite_width, 0, sprite_width, sprite_height).to_image();
+            imageops::resize(&sprite, hand_width, hand_height, imageops::FilterType::Nearest)
+        })
+        .collect();
+
+    Ok(hands)
+}
@@ -0,0 +1,75 @@
+use std::f32::consts::PI;
+use image::{Frame, RgbaImage};
+
+use crate::{command::Command, easing::get_progress, resize::scale_buffer, utils::{align_gif, align_speed, get_delay_centisecs, overlay_centered}};
+
+#[derive(Copy, Clone)]
+pub enum Rhythm {
+    Smooth,
+    Heartbeat,
+}
+
+impl Rhythm {
+    // Returns how far along from the minimum (0) to the maximum (1) scale the emote is
+    fn get_amount(self, progress: f32) -> f32 {
+        match self {
+            Rhythm::Smooth => (1.0 - (2.0 * PI * progress).cos()) / 2.0,
+            Rhythm::Heartbeat => {
+                // Strong beat followed by a weaker one, then a pause
+                let first = get_thump(progress, 0.0, 0.15);
+                let second = get_thump(progress, 0.25, 0.15) * 0.7;
+
+                first.max(second)
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+pub struct PulseOptions {
+    min_scale: f32,
+    max_scale: f32,
+}
+
+impl From<&Command> for PulseOptions {
+    fn from(command: &Command) -> Self {
+        let min = command.option_value("min").map_or(80.0, |min| min.clamp(10.0, 200.0));
+        let max = command.option_value("max").map_or(100.0, |max| max.clamp(10.0, 200.0));
+
+        Self {
+            min_scale: min.min(max) / 100.0,
+            max_scale: max.max(min) / 100.0,
+        }
+    }
+}
+
+pub fn pulse(frames: &mut Vec<Frame>, speed: f32, rhythm: Rhythm, options: PulseOptions) {
+    align_speed(frames, 6.0);
+    let Some(frame) = frames.first() else { return };
+
+    let delay_centisecs = get_delay_centisecs(frame.delay());
+    let centisecs_per_pulse = (80.0 * speed) / 8.0;
+    let pulse_step = delay_centisecs / centisecs_per_pulse;
+    let interval = (1.0 / pulse_step).floor();
+
+    *frames = align_gif(frames, interval as usize);
+
+    for (index, frame) in frames.iter_mut().enumerate() {
+        let progress = get_progress(index, pulse_step);
+        let amount = rhythm.get_amount(progress);
+        let scale = options.min_scale + (options.max_scale - options.min_scale) * amount;
+
+        let scaled_buffer = scale_buffer(frame.buffer(), scale, scale);
+        let mut canvas = RgbaImage::new(frame.buffer().width(), frame.buffer().height());
+        overlay_centered(&mut canvas, &scaled_buffer);
+
+        *frame.buffer_mut() = canvas;
+    }
+}
+
+fn get_thump(progress: f32, start: f32, length: f32) -> f32 {
+    let position = (progress - start) / length;
+    if !(0.0..=1.0).contains(&position) { return 0.0; }
+
+    (PI * position).sin()
+}
@@ -1,4 +1,4 @@
-use image::{imageops::{self, FilterType}, Frame};
+use image::{imageops::{self, FilterType}, Frame, RgbaImage};
 
 pub fn resize(frames: &mut [Frame], scale: (f32, f32)) {
     let Some(frame) = frames.first() else { return; };
@@ -20,3 +20,10 @@ pub fn resize(frames: &mut [Frame], scale: (f32, f32)) {
         *frame.buffer_mut() = new_buffer;
     }
 }
+
+pub fn scale_buffer(buffer: &RgbaImage, scale_x: f32, scale_y: f32) -> RgbaImage {
+    let target_width = (buffer.width() as f32 * scale_x).round().max(1.0) as u32;
+    let target_height = (buffer.height() as f32 * scale_y).round().max(1.0) as u32;
+
+    imageops::resize(buffer, target_width, target_height, FilterType::Nearest)
+}
@@ -0,0 +1,51 @@
+use std::f32::consts::PI;
+use image::Frame;
+
+use crate::{command::Command, easing::get_progress, rotate::rotate_frame_about, utils::{align_gif, align_speed, get_delay_centisecs}};
+
+#[derive(Copy, Clone)]
+pub enum Pivot {
+    Center,
+    Bottom,
+}
+
+#[derive(Copy, Clone)]
+pub struct RockOptions {
+    degrees: f32,
+    pivot: Pivot,
+}
+
+impl From<&Command> for RockOptions {
+    fn from(command: &Command) -> Self {
+        let degrees = command.option_value("angle").map_or(15.0, |angle| angle.clamp(1.0, 90.0));
+        let pivot = if command.has_option("bottom") { Pivot::Bottom } else { Pivot::Center };
+
+        Self { degrees, pivot }
+    }
+}
+
+pub fn rock(frames: &mut Vec<Frame>, speed: f32, options: RockOptions) {
+    align_speed(frames, 6.0);
+    let Some(frame) = frames.first() else { return };
+    let width = frame.buffer().width() as f32;
+    let height = frame.buffer().height() as f32;
+
+    let delay_centisecs = get_delay_centisecs(frame.delay());
+    let centisecs_per_swing = (160.0 * speed) / 8.0;
+    let swing_step = delay_centisecs / centisecs_per_swing;
+    let interval = (1.0 / swing_step).floor();
+
+    *frames = align_gif(frames, interval as usize);
+
+    let center = match options.pivot {
+        Pivot::Center => (width / 2.0, height / 2.0),
+        Pivot::Bottom => (width / 2.0, height),
+    };
+
+    for (index, frame) in frames.iter_mut().enumerate() {
+        let progress = get_progress(index, swing_step);
+        let degrees = (2.0 * PI * progress).sin() * options.degrees;
+
+        rotate_frame_about(frame, degrees, center);
+    }
+}
@@ -8,12 +8,18 @@ pub fn rotate(frames: &mut [Frame], degrees: f32) {
 }
 
 pub fn rotate_frame(frame: &mut Frame, degrees: f32) {
-    let new_buffer = geometric_transformations::rotate_about_center(
+    let center = (frame.buffer().width() as f32 / 2.0, frame.buffer().height() as f32 / 2.0);
+    rotate_frame_about(frame, degrees, center);
+}
+
+pub fn rotate_frame_about(frame: &mut Frame, degrees: f32, center: (f32, f32)) {
+    let new_buffer = geometric_transformations::rotate(
         frame.buffer(),
+        center,
         degrees.to_radians(),
         Interpolation::Nearest,
         Rgba([0, 0, 0, 0])
     );
-    
+
     *frame.buffer_mut() = new_buffer;
 }
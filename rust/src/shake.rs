@@ -1,8 +1,8 @@
 use image::{imageops, Frame, RgbaImage};
 
-use crate::utils::{align_gif, align_speed, get_delay_centisecs};
+use crate::{easing::Easing, utils::{align_gif, align_speed, get_delay_centisecs}};
 
-pub fn shake(frames: &mut Vec<Frame>, strength: f32) {
+pub fn shake(frames: &mut Vec<Frame>, strength: f32, easing: Easing) {
     align_speed(frames, 5.0);
 
     let Some(frame) = frames.first() else { return; };
@@ -25,33 +25,38 @@ pub fn shake(frames: &mut Vec<Frame>, strength: f32) {
     *frames = align_gif(frames, interval);
 
     for (index, frame) in frames.iter_mut().enumerate() {
-        let cycle = index % interval;
+        let position = index % interval;
         let shake_step = interval / 4;
+        let cycle = match easing {
+            // Linear keeps the original timing, leaving the frames that don't fill a whole step unshaken
+            Easing::Linear => position / shake_step,
+            _ => (easing.apply(position as f32 / interval as f32) * 4.0).floor().rem_euclid(4.0) as usize,
+        };
 
         let mut shaken_buffer = RgbaImage::new(width, height);
 
-        if cycle < shake_step {
+        if cycle == 0 {
             imageops::overlay(
                 &mut shaken_buffer,
                 frame.buffer(),
                 -strength_width,
                 -strength_height,
             );
-        } else if cycle < shake_step * 2 {
+        } else if cycle == 1 {
             imageops::overlay(
                 &mut shaken_buffer,
                 frame.buffer(),
                 -strength_width,
                 strength_height,
             );
-        } else if cycle < shake_step * 3 {
+        } else if cycle == 2 {
             imageops::overlay(
                 &mut shaken_buffer,
                 frame.buffer(),
                 strength_width,
                 strength_height,
             );
-        } else if cycle < shake_step * 4 {
+        } else if cycle == 3 {
             imageops::overlay(
                 &mut shaken_buffer,
                 frame.buffer(),
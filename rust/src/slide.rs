@@ -1,47 +1,139 @@
-use image::{Frame, Pixel, Rgba};
+use image::{imageops, Frame, Pixel, Rgba, RgbaImage};
 
-use crate::utils::{align_gif, align_speed, get_delay_centisecs};
+use crate::{command::Command, easing::{get_progress, Easing}, utils::{align_gif, align_speed, get_delay_centisecs}};
 
 #[derive(Copy, Clone)]
 pub enum Direction {
     Forwards,
     Backwards,
+    Up,
+    Down,
+    ForwardsUp,
+    ForwardsDown,
+    BackwardsUp,
+    BackwardsDown,
 }
 
 impl Direction {
-    fn rotate_vec(self) -> fn(&mut [u8], usize) {
+    // Horizontal and vertical sign, positive meaning right and down
+    fn axes(self) -> (i64, i64) {
         match self {
-            Direction::Forwards => <[u8]>::rotate_right,
-            Direction::Backwards => <[u8]>::rotate_left,
+            Direction::Forwards => (1, 0),
+            Direction::Backwards => (-1, 0),
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::ForwardsUp => (1, -1),
+            Direction::ForwardsDown => (1, 1),
+            Direction::BackwardsUp => (-1, -1),
+            Direction::BackwardsDown => (-1, 1),
         }
     }
 }
 
-const CHANNEL_COUNT: usize = <Rgba<u8> as Pixel>::CHANNEL_COUNT as usize;
+#[derive(Copy, Clone)]
+pub enum Edge {
+    Wrap,
+    Transparent,
+}
+
+impl Edge {
+    fn distance(self) -> f32 {
+        match self {
+            Edge::Wrap => 1.0,
+            // Sliding off-screen and back in covers twice the distance
+            Edge::Transparent => 2.0,
+        }
+    }
+}
+
+impl From<&Command> for Edge {
+    fn from(command: &Command) -> Self {
+        if command.has_option("out") {
+            Edge::Transparent
+        } else {
+            Edge::Wrap
+        }
+    }
+}
+
+pub const CHANNEL_COUNT: usize = <Rgba<u8> as Pixel>::CHANNEL_COUNT as usize;
 
-pub fn slide(frames: &mut Vec<Frame>, speed: f32, direction: Direction) {
+pub fn slide(frames: &mut Vec<Frame>, speed: f32, direction: Direction, edge: Edge, easing: Easing) {
     align_speed(frames, 6.0);
     let Some(frame) = frames.first() else { return };
-    let width = frame.buffer().width() as f32;
 
     let delay_centisecs = get_delay_centisecs(frame.delay());
-    let centisecs_per_slide = (50.0 * speed) / 8.0;
-    let shift_size = (width * delay_centisecs) / centisecs_per_slide;
-    let interval = (width / shift_size).floor();
+    let centisecs_per_slide = ((50.0 * speed) / 8.0) * edge.distance();
+    let slide_step = delay_centisecs / centisecs_per_slide; // Part of a full slide between frames
+    let interval = (1.0 / slide_step).floor();
 
     *frames = align_gif(frames, interval as usize);
 
-    let row_len = width as usize * CHANNEL_COUNT;
-    let rotate_vec = direction.rotate_vec();
+    let (horizontal, vertical) = direction.axes();
 
     for (index, frame) in frames.iter_mut().enumerate() {
-        let shift = ((index as f32 * shift_size) % width).round() as usize;
-        shift_frame_data(frame, shift * CHANNEL_COUNT, row_len, rotate_vec);
+        // Overshooting easings can leave the cycle, wrap them back into it
+        let progress = easing.apply(get_progress(index, slide_step)).rem_euclid(1.0);
+
+        match edge {
+            Edge::Wrap => wrap_frame(frame, progress, horizontal, vertical),
+            Edge::Transparent => offscreen_frame(frame, progress, horizontal, vertical),
+        }
+    }
+}
+
+pub fn rotate_vec(sign: i64) -> fn(&mut [u8], usize) {
+    if sign > 0 {
+        <[u8]>::rotate_right
+    } else {
+        <[u8]>::rotate_left
+    }
+}
+
+fn wrap_frame(frame: &mut Frame, progress: f32, horizontal: i64, vertical: i64) {
+    let width = frame.buffer().width() as usize;
+    let height = frame.buffer().height() as usize;
+    let row_len = width * CHANNEL_COUNT;
+
+    if horizontal != 0 {
+        let shift = (progress * width as f32).round() as usize;
+        shift_frame_data(frame.buffer_mut(), shift * CHANNEL_COUNT, row_len, rotate_vec(horizontal));
+    }
+
+    if vertical != 0 {
+        // Rows are stored contiguously, so rotating the whole buffer by whole rows shifts it vertically
+        let shift = (progress * height as f32).round() as usize;
+        rotate_vec(vertical)(frame.buffer_mut(), shift * row_len);
     }
 }
 
-fn shift_frame_data(frame: &mut Frame, shift: usize, row_len: usize, rotate_vec: fn(&mut [u8], usize)) {
-    for row in frame.buffer_mut().chunks_exact_mut(row_len) {
+pub fn shift_frame_data(data: &mut [u8], shift: usize, row_len: usize, rotate_vec: fn(&mut [u8], usize)) {
+    for row in data.chunks_exact_mut(row_len) {
         rotate_vec(row, shift);
     }
 }
+
+fn offscreen_frame(frame: &mut Frame, progress: f32, horizontal: i64, vertical: i64) {
+    let width = frame.buffer().width();
+    let height = frame.buffer().height();
+
+    let dx = horizontal * offscreen_offset(progress, width);
+    let dy = vertical * offscreen_offset(progress, height);
+
+    let mut slid_buffer = RgbaImage::new(width, height);
+    imageops::overlay(&mut slid_buffer, frame.buffer(), dx, dy);
+
+    *frame.buffer_mut() = slid_buffer;
+}
+
+fn offscreen_offset(progress: f32, size: u32) -> i64 {
+    // First half of the cycle slides out, second half comes back in from the opposite edge
+    let size = size as f32;
+    let offset = (progress * size * 2.0).round();
+
+    if offset < size {
+        offset as i64
+    } else {
+        (offset - size * 2.0) as i64
+    }
+}
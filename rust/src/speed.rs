@@ -0,0 +1,124 @@
+use std::mem;
+use image::Frame;
+
+use crate::utils::{get_delay, get_shown_delay_centisecs};
+
+// Browsers slow down anything faster than this
+const MIN_DELAY_CENTISECS: f32 = 2.0;
+
+pub fn speed(frames: &mut Vec<Frame>, delay_centisecs: f32) {
+    let delays = vec![delay_centisecs; frames.len()];
+    retime(frames, &delays);
+}
+
+pub fn speed_multiply(frames: &mut Vec<Frame>, multiplier: f32) {
+    if !multiplier.is_finite() || multiplier <= 0.0 { return; }
+
+    let delays: Vec<f32> = frames
+        .iter()
+        .map(|frame| get_shown_delay_centisecs(frame.delay()) / multiplier)
+        .collect();
+
+    retime(frames, &delays);
+}
+
+pub fn hyperspeed(frames: &mut Vec<Frame>, interval: f32) {
+    let interval = (interval as usize).max(2);
+    if frames.len() <= interval * 2 { return speed(frames, MIN_DELAY_CENTISECS); }
+
+    let mut index = 0;
+    frames.retain_mut(|frame| {
+        let retain = index % interval == 0;
+        if retain { set_speed(frame, MIN_DELAY_CENTISECS as u32); }
+
+        index += 1;
+        retain
+    });
+}
+
+fn set_speed(frame: &mut Frame, delay_centisecs: u32) {
+    let left = frame.left();
+    let top = frame.top();
+
+    *frame = Frame::from_parts(
+        mem::take(frame.buffer_mut()),
+        left,
+        top,
+        get_delay(delay_centisecs),
+    );
+}
+
+fn retime(frames: &mut Vec<Frame>, delays: &[f32]) {
+    let mut retimed = Vec::with_capacity(frames.len());
+    let mut pending: Option<Frame> = None;
+
+    // GIF delays are whole centiseconds, so carry the rounding error over to the next frame
+    let mut elapsed = 0.0;
+    let mut emitted = 0.0;
+
+    for (frame, &delay) in mem::take(frames).into_iter().zip(delays) {
+        // Frames that would be shown too briefly are merged into the one before them
+        let mut frame = pending.take().unwrap_or(frame);
+        elapsed += delay;
+
+        let frame_delay = elapsed.round() - emitted;
+        if frame_delay < MIN_DELAY_CENTISECS {
+            pending = Some(frame);
+            continue;
+        }
+
+        set_speed(&mut frame, frame_delay as u32);
+        retimed.push(frame);
+        emitted += frame_delay;
+    }
+
+    if let Some(mut frame) = pending {
+        let frame_delay = (elapsed.round() - emitted).max(MIN_DELAY_CENTISECS);
+        set_speed(&mut frame, frame_delay as u32);
+        retimed.push(frame);
+    }
+
+    *frames = retimed;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{blank_frames, get_delay_centisecs};
+
+    fn get_delays(frames: &[Frame]) -> Vec<f32> {
+        frames.iter().map(|frame| get_delay_centisecs(frame.delay())).collect()
+    }
+
+    #[test]
+    fn speed_multiply_treats_zero_delay_as_browser_default() {
+        let mut frames = blank_frames(6, 0);
+        speed_multiply(&mut frames, 2.0);
+
+        assert_eq!(get_delays(&frames), vec![5.0; 6]);
+    }
+
+    #[test]
+    fn speed_multiply_ignores_invalid_multiplier() {
+        let mut frames = blank_frames(3, 4);
+        speed_multiply(&mut frames, f32::NAN);
+
+        assert_eq!(get_delays(&frames), vec![4.0; 3]);
+    }
+
+    #[test]
+    fn retime_carries_rounding_to_next_frame() {
+        let mut frames = blank_frames(4, 4);
+        speed(&mut frames, 2.5);
+
+        assert_eq!(get_delays(&frames), vec![3.0, 2.0, 3.0, 2.0]);
+    }
+
+    #[test]
+    fn retime_merges_frames_below_minimum_delay() {
+        let mut frames = blank_frames(6, 4);
+        speed(&mut frames, 1.0);
+
+        assert_eq!(get_delays(&frames), vec![2.0, 2.0, 2.0]);
+    }
+}
@@ -0,0 +1,196 @@
+use std::f32::consts::PI;
+use image::{imageops, Frame, GenericImageView, ImageFormat, RgbaImage};
+use wasm_bindgen::JsError;
+
+use crate::{
+    command::Command,
+    compose::Anchor,
+    easing::{get_progress, Easing},
+    resize::scale_buffer,
+    utils::{align_gif, align_speed, get_delay_centisecs},
+};
+
+#[derive(Copy, Clone)]
+enum Motion {
+    // Only the sprites animate
+    Still,
+    // Floats up and down
+    Bob,
+    // Slides down, e.g. a sweat drop running down
+    Fall,
+    // Grows in and stays
+    Pop,
+    // Drops in from above and stays
+    DropIn,
+}
+
+struct Sticker {
+    name: &'static str,
+    // Sprite sheet with every frame next to each other
+    sprites: &'static [u8],
+    frames: u32,
+    // Where the sticker sits on the emote, 0 being left or top and 1 being right or bottom
+    position: (f32, f32),
+    // Width relative to the emote
+    scale: f32,
+    motion: Motion,
+}
+
+// To add a sticker, put its sprite sheet in assets/stickers and list it here
+static STICKERS: [Sticker; 7] = [
+    Sticker {
+        name: "bubble",
+        sprites: include_bytes!("../assets/stickers/bubble.png"),
+        frames: 1,
+        position: (1.0, 0.0),
+        scale: 0.5,
+        motion: Motion::Pop,
+    },
+    Sticker {
+        name: "sweat",
+        sprites: include_bytes!("../assets/stickers/sweat.png"),
+        frames: 1,
+        position: (0.85, 0.1),
+        scale: 0.2,
+        motion: Motion::Fall,
+    },
+    Sticker {
+        name: "anger",
+        sprites: include_bytes!("../assets/stickers/anger.png"),
+        frames: 2,
+        position: (0.95, 0.05),
+        scale: 0.35,
+        motion: Motion::Still,
+    },
+    Sticker {
+        name: "hearts",
+        sprites: include_bytes!("../assets/stickers/hearts.png"),
+        frames: 2,
+        position: (0.5, 0.0),
+        scale: 0.5,
+        motion: Motion::Bob,
+    },
+    Sticker {
+        name: "sparkles",
+        sprites: include_bytes!("../assets/stickers/sparkles.png"),
+        frames: 3,
+        position: (0.5, 0.5),
+        scale: 1.0,
+        motion: Motion::Still,
+    },
+    Sticker {
+        name: "question",
+        sprites: include_bytes!("../assets/stickers/question.png"),
+        frames: 2,
+        position: (1.0, 0.0),
+        scale: 0.45,
+        motion: Motion::Bob,
+    },
+    Sticker {
+        name: "sunglasses",
+        sprites: include_bytes!("../assets/stickers/sunglasses.png"),
+        frames: 1,
+        position: (0.5, 0.3),
+        scale: 0.8,
+        motion: Motion::DropIn,
+    },
+];
+
+#[derive(Copy, Clone)]
+pub struct StickerOptions {
+    sticker: Option<&'static Sticker>,
+    anchor: Option<Anchor>,
+    // Width in percent of the emote
+    scale: Option<f32>,
+}
+
+impl From<&Command> for StickerOptions {
+    fn from(command: &Command) -> Self {
+        let sticker = STICKERS.iter().find(|sticker| command.has_option(sticker.name));
+        let scale = command.options
+            .iter()
+            .find_map(|option| option.parse::<f32>().ok())
+            .map(|scale| scale.clamp(5.0, 200.0));
+
+        Self {
+            sticker,
+            anchor: Anchor::from_options(command),
+            scale,
+        }
+    }
+}
+
+pub fn sticker(frames: &mut Vec<Frame>, speed: f32, options: StickerOptions) -> Result<(), JsError> {
+    let Some(sticker) = options.sticker else { return Ok(()) };
+
+    align_speed(frames, 6.0);
+    let Some(frame) = frames.first() else { return Ok(()) };
+    let width = frame.buffer().width();
+    let height = frame.buffer().height();
+
+    let scale = options.scale.map_or(sticker.scale, |scale| scale / 100.0);
+    let sprites = get_sprites(sticker, (width as f32 * scale).round().max(1.0) as u32)?;
+    let sprite_width = sprites[0].width() as f32;
+    let sprite_height = sprites[0].height() as f32;
+
+    let (anchor_x, anchor_y) = options.anchor.map_or(sticker.position, Anchor::position);
+    let x = (width as f32 - sprite_width) * anchor_x;
+    let y = (height as f32 - sprite_height) * anchor_y;
+
+    let delay_centisecs = get_delay_centisecs(frame.delay());
+    let centisecs_per_cycle = (100.0 * speed) / 8.0;
+    let step = delay_centisecs / centisecs_per_cycle;
+    let interval = (1.0 / step).floor().max(1.0) as usize;
+
+    *frames = align_gif(frames, interval);
+
+    for (index, frame) in frames.iter_mut().enumerate() {
+        let progress = get_progress(index, step);
+        let sprite = &sprites[(progress * sprites.len() as f32) as usize % sprites.len()];
+
+        let (offset_y, size) = match sticker.motion {
+            Motion::Still => (0.0, 1.0),
+            Motion::Bob => ((2.0 * PI * progress).sin() * height as f32 * 0.04, 1.0),
+            Motion::Fall => (progress * height as f32 * 0.2, 1.0),
+            // Both settle within the first part of the cycle and stay for the rest
+            Motion::Pop => (0.0, Easing::Elastic.apply((progress / 0.3).min(1.0))),
+            Motion::DropIn => {
+                let eased = Easing::Bounce.apply((progress / 0.5).min(1.0));
+                ((eased - 1.0) * (y + sprite_height), 1.0)
+            },
+        };
+
+        if size <= 0.0 { continue; }
+
+        if size == 1.0 {
+            imageops::overlay(frame.buffer_mut(), sprite, x.round() as i64, (y + offset_y).round() as i64);
+        } else {
+            // Grow from the center of the sticker
+            let scaled_sprite = scale_buffer(sprite, size, size);
+            let scaled_x = x + (sprite_width - scaled_sprite.width() as f32) / 2.0;
+            let scaled_y = y + offset_y + (sprite_height - scaled_sprite.height() as f32) / 2.0;
+
+            imageops::overlay(frame.buffer_mut(), &scaled_sprite, scaled_x.round() as i64, scaled_y.round() as i64);
+        }
+    }
+
+    Ok(())
+}
+
+fn get_sprites(sticker: &Sticker, width: u32) -> Result<Vec<RgbaImage>, JsError> {
+    let sheet = image::load_from_memory_with_format(sticker.sprites, ImageFormat::Png)?.into_rgba8();
+    let sprite_width = sheet.width() / sticker.frames;
+    let sprite_height = sheet.height();
+
+    // Keep the aspect ratio of the sprites
+    let height = (width as f32 * sprite_height as f32 / sprite_width as f32).round().max(1.0) as u32;
+
+    let sprites = (0..sticker.frames)
+        .map(|index| {
+            let sprite = sheet.view(index * sprite_width, 0, sprite_width, sprite_height).to_image();
+            imageops::resize(&sprite, width, height, imageops::FilterType::Nearest)
+        })
+        .collect();
+
+    Ok(sprites)
+}
@@ -0,0 +1,270 @@
+use image::{imageops, Frame, GrayImage, Rgba, RgbaImage};
+use rusttype::{point, Font, PositionedGlyph, Scale};
+use wasm_bindgen::JsError;
+
+use crate::{alpha::dilate_mask, command::Command, easing::get_progress, utils::{align_gif, align_speed, get_delay_centisecs}};
+
+// Bold condensed font, trimmed down to Latin characters to keep the size down
+const FONT: &[u8] = include_bytes!("../assets/DejaVuSansCondensed-Bold.ttf");
+const MIN_FONT_SIZE: f32 = 6.0;
+// Space kept free between the text and the edges, in part of the emote size
+const MARGIN: f32 = 0.04;
+// Part of the typewriter cycle spent typing, the rest is spent showing the full text
+const TYPING: f32 = 0.6;
+
+#[derive(Copy, Clone)]
+pub enum Position {
+    Top,
+    Bottom,
+    Center,
+}
+
+#[derive(Copy, Clone)]
+pub enum Animation {
+    Still,
+    Typewriter,
+    Marquee,
+}
+
+#[derive(Copy, Clone)]
+pub struct TextOptions {
+    position: Position,
+    animation: Animation,
+    color: Rgba<u8>,
+    outline_color: Rgba<u8>,
+}
+
+impl From<&Command> for TextOptions {
+    fn from(command: &Command) -> Self {
+        let position = if command.has_option("top") {
+            Position::Top
+        } else if command.has_option("center") {
+            Position::Center
+        } else {
+            Position::Bottom
+        };
+
+        let animation = if command.has_option("typewriter") {
+            Animation::Typewriter
+        } else if command.has_option("marquee") {
+            Animation::Marquee
+        } else {
+            Animation::Still
+        };
+
+        // Keep the outline readable against the text color
+        let color = command.color_option().unwrap_or(Rgba([255, 255, 255, 255]));
+        let luma = color.0[0] as f32 * 0.299 + color.0[1] as f32 * 0.587 + color.0[2] as f32 * 0.114;
+        let outline_color = if luma > 128.0 { Rgba([0, 0, 0, 255]) } else { Rgba([255, 255, 255, 255]) };
+
+        Self {
+            position,
+            animation,
+            color,
+            outline_color,
+        }
+    }
+}
+
+struct TextBlock {
+    glyphs: Vec<PositionedGlyph<'static>>,
+    width: u32,
+    height: u32,
+    outline: f32,
+}
+
+pub fn text(frames: &mut Vec<Frame>, speed: f32, text: &str, options: TextOptions) -> Result<(), JsError> {
+    let font = Font::try_from_bytes(FONT).ok_or_else(|| JsError::new("Failed to load font"))?;
+    let Some(frame) = frames.first() else { return Ok(()) };
+    let width = frame.buffer().width();
+    let height = frame.buffer().height();
+
+    // Meme text is all caps, captions are left as written
+    let text = match options.position {
+        Position::Center => text.trim().to_string(),
+        _ => text.trim().to_uppercase(),
+    };
+    if text.is_empty() { return Ok(()); }
+
+    let block = match options.animation {
+        Animation::Marquee => fit_line(&font, &text, height),
+        _ => fit_text(&font, &text, width, height, options.position),
+    };
+
+    let margin = (height as f32 * MARGIN).round() as i64;
+    let y = match options.position {
+        Position::Top => margin,
+        Position::Bottom => height as i64 - block.height as i64 - margin,
+        Position::Center => (height as i64 - block.height as i64) / 2,
+    };
+    let centered_x = (width as i64 - block.width as i64) / 2;
+
+    match options.animation {
+        Animation::Still => {
+            let layer = render_block(&block, block.glyphs.len(), options);
+
+            for frame in frames {
+                imageops::overlay(frame.buffer_mut(), &layer, centered_x, y);
+            }
+        },
+        Animation::Typewriter => {
+            let (step, interval) = get_step(frames, (200.0 * speed) / 8.0);
+            *frames = align_gif(frames, interval);
+
+            for (index, frame) in frames.iter_mut().enumerate() {
+                let progress = (get_progress(index, step) / TYPING).min(1.0);
+                let visible = (progress * block.glyphs.len() as f32).ceil() as usize;
+
+                let layer = render_block(&block, visible, options);
+                imageops::overlay(frame.buffer_mut(), &layer, centered_x, y);
+            }
+        },
+        Animation::Marquee => {
+            // Scroll all the way through from the right edge until the text is gone on the left
+            let distance = (width + block.width) as f32;
+            let (step, interval) = get_step(frames, (100.0 * speed) / 8.0 * distance / width as f32);
+            *frames = align_gif(frames, interval);
+
+            let layer = render_block(&block, block.glyphs.len(), options);
+
+            for (index, frame) in frames.iter_mut().enumerate() {
+                let progress = get_progress(index, step);
+                let x = width as i64 - (progress * distance).round() as i64;
+
+                imageops::overlay(frame.buffer_mut(), &layer, x, y);
+            }
+        },
+    }
+
+    Ok(())
+}
+
+fn get_step(frames: &mut Vec<Frame>, centisecs_per_cycle: f32) -> (f32, usize) {
+    align_speed(frames, 6.0);
+    let delay_centisecs = frames.first().map_or(2.0, |frame| get_delay_centisecs(frame.delay()));
+
+    let step = delay_centisecs / centisecs_per_cycle;
+    (step, (1.0 / step).floor().max(1.0) as usize)
+}
+
+fn fit_text(font: &Font<'static>, text: &str, width: u32, height: u32, position: Position) -> TextBlock {
+    let max_width = width as f32 * (1.0 - MARGIN * 2.0);
+    let max_height = height as f32 * match position {
+        Position::Center => 1.0 - MARGIN * 2.0,
+        _ => 0.3,
+    };
+
+    // Shrink the text until it fits, wrapping it over more lines as it gets smaller
+    let mut size = (height as f32 * 0.2).max(MIN_FONT_SIZE);
+    loop {
+        let scale = Scale::uniform(size);
+        let lines = wrap_text(font, text, scale, max_width);
+
+        let text_width = lines.iter().map(|line| measure_line(font, line, scale)).fold(0.0, f32::max);
+        let text_height = lines.len() as f32 * get_line_height(font, scale);
+
+        let fits = text_width <= max_width && text_height <= max_height;
+        if fits || size <= MIN_FONT_SIZE {
+            return layout_lines(font, &lines, scale);
+        }
+
+        size = (size * 0.9).max(MIN_FONT_SIZE);
+    }
+}
+
+fn fit_line(font: &Font<'static>, text: &str, height: u32) -> TextBlock {
+    // Scrolling text stays on one line, so only its height matters
+    let size = (height as f32 * 0.25).max(MIN_FONT_SIZE);
+    layout_lines(font, &[text.to_string()], Scale::uniform(size))
+}
+
+fn wrap_text(font: &Font<'static>, text: &str, scale: Scale, max_width: f32) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if measure_line(font, &format!("{} {}", line, word), scale) <= max_width => {
+                line.push(' ');
+                line.push_str(word);
+            },
+            _ => lines.push(word.to_string()),
+        }
+    }
+
+    lines
+}
+
+fn measure_line(font: &Font<'static>, line: &str, scale: Scale) -> f32 {
+    font.layout(line, scale, point(0.0, 0.0))
+        .last()
+        .map_or(0.0, |glyph| glyph.position().x + glyph.unpositioned().h_metrics().advance_width)
+}
+
+fn get_line_height(font: &Font<'static>, scale: Scale) -> f32 {
+    let metrics = font.v_metrics(scale);
+    metrics.ascent - metrics.descent + metrics.line_gap
+}
+
+fn layout_lines(font: &Font<'static>, lines: &[String], scale: Scale) -> TextBlock {
+    let outline = (scale.y / 10.0).max(1.0);
+    let line_height = get_line_height(font, scale);
+    let ascent = font.v_metrics(scale).ascent;
+
+    let widths: Vec<f32> = lines.iter().map(|line| measure_line(font, line, scale)).collect();
+    let text_width = widths.iter().copied().fold(0.0, f32::max);
+
+    let glyphs = lines
+        .iter()
+        .zip(&widths)
+        .enumerate()
+        .flat_map(|(index, (line, line_width))| {
+            // Every line is centered within the block
+            let x = outline + (text_width - line_width) / 2.0;
+            let y = outline + index as f32 * line_height + ascent;
+
+            font.layout(line, scale, point(x, y)).collect::<Vec<_>>()
+        })
+        .collect();
+
+    TextBlock {
+        glyphs,
+        width: (text_width + outline * 2.0).ceil() as u32 + 1,
+        height: (lines.len() as f32 * line_height + outline * 2.0).ceil() as u32,
+        outline,
+    }
+}
+
+fn render_block(block: &TextBlock, visible: usize, options: TextOptions) -> RgbaImage {
+    let mut coverage = GrayImage::new(block.width, block.height);
+
+    for glyph in block.glyphs.iter().take(visible) {
+        let Some(bounds) = glyph.pixel_bounding_box() else { continue };
+
+        glyph.draw(|x, y, value| {
+            let x = x as i32 + bounds.min.x;
+            let y = y as i32 + bounds.min.y;
+            if x < 0 || y < 0 || x >= block.width as i32 || y >= block.height as i32 { return; }
+
+            // Glyphs can overlap a little, keep the strongest coverage
+            let pixel = coverage.get_pixel_mut(x as u32, y as u32);
+            pixel.0[0] = pixel.0[0].max((value * 255.0).round() as u8);
+        });
+    }
+
+    let outline = dilate_mask(&coverage, block.outline);
+
+    RgbaImage::from_fn(block.width, block.height, |x, y| {
+        let fill = coverage.get_pixel(x, y).0[0] as f32 / 255.0;
+        let edge = outline.get_pixel(x, y).0[0] as f32 / 255.0 * (1.0 - fill);
+
+        let alpha = fill + edge;
+        if alpha <= 0.0 { return Rgba([0, 0, 0, 0]); }
+
+        let channel = |index: usize| {
+            let value = options.color.0[index] as f32 * fill + options.outline_color.0[index] as f32 * edge;
+            (value / alpha).round() as u8
+        };
+
+        Rgba([channel(0), channel(1), channel(2), (alpha * 255.0).round() as u8])
+    })
+}
@@ -0,0 +1,134 @@
+use image::{Delay, Frame, Rgba, RgbaImage};
+
+use crate::{alpha::from_premultiplied, command::Command, utils::{align_speed, get_delay_centisecs}};
+
+const DEFAULT_GHOSTS: u32 = 3;
+const MAX_GHOSTS: u32 = 8;
+const DEFAULT_SAMPLES: u32 = 4;
+const MAX_SAMPLES: u32 = 8;
+// Opacity of the ghost right behind the emote, older ones fade out from there
+const GHOST_OPACITY: f32 = 0.6;
+// Frame rate the samples are merged back to, fast enough for every motion effect
+const BLUR_DELAY_CENTISECS: f32 = 4.0;
+
+#[derive(Copy, Clone)]
+pub enum Trail {
+    // Amount of earlier frames drawn behind the emote
+    Ghosts(u32),
+    // Amount of frames rendered and blended together for every frame
+    Blur(u32),
+}
+
+impl Trail {
+    // Any effect can be followed by trail or blur, with an optional strength like trail5 or blur6
+    pub fn from_options(command: &Command) -> Option<Self> {
+        if let Some(ghosts) = command.option_value("trail") {
+            Some(Trail::Ghosts((ghosts as u32).clamp(1, MAX_GHOSTS)))
+        } else if command.has_option("trail") {
+            Some(Trail::Ghosts(DEFAULT_GHOSTS))
+        } else if let Some(samples) = command.option_value("blur") {
+            Some(Trail::Blur((samples as u32).clamp(2, MAX_SAMPLES)))
+        } else if command.has_option("blur") {
+            Some(Trail::Blur(DEFAULT_SAMPLES))
+        } else {
+            None
+        }
+    }
+}
+
+pub fn trail(frames: &mut [Frame], ghosts: u32) {
+    if frames.len() < 2 { return; }
+    let ghosts = (ghosts as usize).clamp(1, MAX_GHOSTS as usize).min(frames.len() - 1);
+
+    let buffers: Vec<RgbaImage> = frames.iter().map(|frame| frame.buffer().clone()).collect();
+    let count = buffers.len();
+
+    for (index, frame) in frames.iter_mut().enumerate() {
+        for (x, y, pixel) in frame.buffer_mut().enumerate_pixels_mut() {
+            if pixel.0[3] == 255 { continue; }
+
+            // Draw the oldest ghost first so newer ones end up on top, wrapping around the loop
+            let mut color = [0.0_f32; 3];
+            let mut alpha = 0.0_f32;
+
+            for age in (1..=ghosts).rev() {
+                let ghost = buffers[(index + count - age) % count].get_pixel(x, y);
+                let fade = GHOST_OPACITY * (ghosts + 1 - age) as f32 / (ghosts + 1) as f32;
+                draw_over(&mut color, &mut alpha, ghost, fade);
+            }
+
+            // The emote itself goes over its ghosts
+            draw_over(&mut color, &mut alpha, pixel, 1.0);
+
+            *pixel = from_premultiplied(color, alpha);
+        }
+    }
+}
+
+// Splits every frame into samples that are shown for a fraction of the time, so the effect after it moves in smaller steps
+pub fn supersample(frames: &mut Vec<Frame>, samples: u32) {
+    align_speed(frames, BLUR_DELAY_CENTISECS);
+
+    *frames = frames
+        .iter()
+        .flat_map(|frame| {
+            let delay_ms = (get_delay_centisecs(frame.delay()) * 10.0).round() as u32;
+            let delay = Delay::from_numer_denom_ms(delay_ms, samples);
+
+            (0..samples).map(move |_| Frame::from_parts(frame.buffer().clone(), frame.left(), frame.top(), delay))
+        })
+        .collect();
+}
+
+// Blends the samples from supersample back into whole frames
+pub fn merge_samples(frames: &mut Vec<Frame>, samples: u32) {
+    if frames.is_empty() { return; }
+
+    // Effects can add frames to fit their cycle, so spread any leftovers over the groups
+    let count = frames.len();
+    let groups = (count / samples as usize).max(1);
+
+    *frames = (0..groups)
+        .map(|group| {
+            let samples = &frames[group * count / groups..(group + 1) * count / groups];
+            let delay_centisecs: f32 = samples.iter().map(|frame| get_delay_centisecs(frame.delay())).sum();
+
+            let buffer = blend_samples(samples);
+
+            let delay = Delay::from_numer_denom_ms((delay_centisecs * 10.0).round() as u32, 1);
+            Frame::from_parts(buffer, samples[0].left(), samples[0].top(), delay)
+        })
+        .collect();
+}
+
+fn blend_samples(samples: &[Frame]) -> RgbaImage {
+    let first = samples[0].buffer();
+
+    RgbaImage::from_fn(first.width(), first.height(), |x, y| {
+        let mut color = [0.0_f32; 3];
+        let mut alpha = 0.0_f32;
+
+        // Weigh colors by their alpha so transparent pixels don't darken the edges
+        for sample in samples {
+            let Some(pixel) = sample.buffer().get_pixel_checked(x, y) else { continue };
+            let opacity = pixel.0[3] as f32 / 255.0;
+
+            for (value, &channel) in color.iter_mut().zip(&pixel.0[..3]) {
+                *value += channel as f32 * opacity;
+            }
+            alpha += opacity;
+        }
+
+        let count = samples.len() as f32;
+        from_premultiplied(color.map(|value| value / count), alpha / count)
+    })
+}
+
+fn draw_over(color: &mut [f32; 3], alpha: &mut f32, pixel: &Rgba<u8>, fade: f32) {
+    let opacity = pixel.0[3] as f32 / 255.0 * fade;
+
+    for (value, &channel) in color.iter_mut().zip(&pixel.0[..3]) {
+        *value = channel as f32 * opacity + *value * (1.0 - opacity);
+    }
+    *alpha = opacity + *alpha * (1.0 - opacity);
+}
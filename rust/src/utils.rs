@@ -1,19 +1,24 @@
 use std::io::Cursor;
-use image::{Frame, codecs::{gif::GifDecoder, png::PngDecoder}, AnimationDecoder, Delay, DynamicImage, Rgba, RgbaImage};
+use image::{Frame, codecs::{gif::GifDecoder, png::PngDecoder}, imageops, AnimationDecoder, Delay, DynamicImage, Rgba, RgbaImage};
 use js_sys::Math;
 use wasm_bindgen::JsError;
 
-use crate::{command::Command, speed};
+use crate::{command::Command, speed::speed};
 
 pub fn get_frames_and_scale(data: &[u8], format_type: &str, commands: &mut Vec<Command>) -> Result<(Vec<Frame>, (f32, f32)), JsError> {
     let scale = get_scale(commands);
 
+    if format_type == "gif" && scale.0 == 1.0 && scale.1 == 1.0 && commands.is_empty() {
+        return Ok((vec![], scale));
+    }
+
+    let frames = get_frames(data, format_type)?;
+    Ok((frames, scale))
+}
+
+pub fn get_frames(data: &[u8], format_type: &str) -> Result<Vec<Frame>, JsError> {
     let frames = match format_type {
         "gif" => {
-            if scale.0 == 1.0 && scale.1 == 1.0 && commands.is_empty() {
-                return Ok((vec![], scale));
-            }
-
             GifDecoder::new(Cursor::new(data))?
                 .into_frames()
                 .collect_frames()?
@@ -44,7 +49,7 @@ pub fn get_frames_and_scale(data: &[u8], format_type: &str, commands: &mut Vec<C
         _ => return Err(JsError::new(format!("Unsupported format: {}", format_type).as_str()))
     };
 
-    Ok((frames, scale))
+    Ok(frames)
 }
 
 fn adjust_png_transparency_for_gif(image: &mut RgbaImage) {
@@ -54,6 +59,26 @@ fn adjust_png_transparency_for_gif(image: &mut RgbaImage) {
     }
 }
 
+pub fn dither_alpha(image: &mut RgbaImage) {
+    // GIFs can't do partial transparency, so approximate it with an ordered dither pattern
+    const BAYER: [[u8; 4]; 4] = [
+        [0, 8, 2, 10],
+        [12, 4, 14, 6],
+        [3, 11, 1, 9],
+        [15, 7, 13, 5],
+    ];
+
+    for (x, y, pixel) in image.enumerate_pixels_mut() {
+        let threshold = (BAYER[y as usize % 4][x as usize % 4] as f32 + 0.5) * 16.0;
+
+        if (pixel.0[3] as f32) < threshold {
+            *pixel = Rgba([0, 0, 0, 0]);
+        } else {
+            pixel.0[3] = 255;
+        }
+    }
+}
+
 pub fn align_gif(frames: &[Frame], interval: usize) -> Vec<Frame> {
     // Duplicate frames until interval is reached
     let copies = (interval.saturating_sub(1) / frames.len()) + 1;
@@ -112,17 +137,49 @@ pub fn align_speed(frames: &mut Vec<Frame>, target_delay_centisecs: f32) {
         new_delay_centisecs = delay_centisecs / (aligned_frames.len() as f32 / frames.len() as f32);
     }
 
-    speed(&mut aligned_frames, new_delay_centisecs);
+    // Keep every frame on the same whole delay so effects can rely on a constant frame rate
+    speed(&mut aligned_frames, new_delay_centisecs.floor());
     *frames = aligned_frames
 }
 
+pub fn overlay_centered(canvas: &mut RgbaImage, buffer: &RgbaImage) {
+    let dx = ((buffer.width() as f32 - canvas.width() as f32) / 2.0).round() as i64;
+    let dy = ((buffer.height() as f32 - canvas.height() as f32) / 2.0).round() as i64;
+
+    imageops::overlay(canvas, buffer, -dx, -dy);
+}
+
+pub fn overlay_bottom(canvas: &mut RgbaImage, buffer: &RgbaImage) {
+    let dx = ((buffer.width() as f32 - canvas.width() as f32) / 2.0).round() as i64;
+    let dy = buffer.height() as i64 - canvas.height() as i64;
+
+    imageops::overlay(canvas, buffer, -dx, -dy);
+}
+
 pub fn get_delay(delay_centisecs: u32) -> Delay {
     Delay::from_numer_denom_ms(delay_centisecs * 10, 1)
 }
 
 pub fn get_delay_centisecs(delay: Delay) -> f32 {
     let (numerator, denominator) = delay.numer_denom_ms();
-    (numerator as f32 * denominator as f32) / 10.0
+    numerator as f32 / denominator as f32 / 10.0
+}
+
+// Browsers play frames shorter than this at their default delay instead
+pub fn get_shown_delay_centisecs(delay: Delay) -> f32 {
+    const MIN_SHOWN_DELAY_CENTISECS: f32 = 2.0;
+    const DEFAULT_DELAY_CENTISECS: f32 = 10.0;
+
+    let delay_centisecs = get_delay_centisecs(delay);
+    if delay_centisecs < MIN_SHOWN_DELAY_CENTISECS { DEFAULT_DELAY_CENTISECS } else { delay_centisecs }
+}
+
+// Empty frames with the given delay, for testing timing logic
+#[cfg(test)]
+pub fn blank_frames(amount: usize, delay_centisecs: u32) -> Vec<Frame> {
+    (0..amount)
+        .map(|_| Frame::from_parts(RgbaImage::new(1, 1), 0, 0, get_delay(delay_centisecs)))
+        .collect()
 }
 
 pub fn get_random_u32(min: u32, max: u32) -> u32 {
@@ -132,7 +189,38 @@ pub fn get_random_u32(min: u32, max: u32) -> u32 {
     (Math::random() * (max - min) + min).floor() as u32
 }
 
-fn get_scale(commands: &mut Vec<Command>) -> (f32, f32) {
+// Small SplitMix64 generator for effects that need to be reproducible
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut value = self.state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+
+        (value ^ (value >> 31)) as u32
+    }
+
+    pub fn next_f32(&mut self) -> f32 {
+        self.next_u32() as f32 / u32::MAX as f32
+    }
+
+    // Same range as get_random_u32: min inclusive, max exclusive
+    pub fn range(&mut self, min: u32, max: u32) -> u32 {
+        if max <= min { return min; }
+        min + self.next_u32() % (max - min)
+    }
+}
+
+pub fn get_scale(commands: &mut Vec<Command>) -> (f32, f32) {
     let mut scale_x: f32 = 1.0;
     let mut scale_y: f32 = 1.0;
 
@@ -153,3 +241,15 @@ fn get_scale(commands: &mut Vec<Command>) -> (f32, f32) {
 
     (scale_x, scale_y)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_centisecs_divides_fractional_delays() {
+        assert_eq!(get_delay_centisecs(get_delay(5)), 5.0);
+        // Motion blur splits frames into delays like 20ms / 3, which image keeps as a fraction
+        assert!((get_delay_centisecs(Delay::from_numer_denom_ms(20, 3)) - 2.0 / 3.0).abs() < 1e-6);
+    }
+}
@@ -1,6 +1,6 @@
 use image::{imageops, Frame, GenericImageView, RgbaImage};
 
-use crate::utils::{align_gif, align_speed, get_delay_centisecs};
+use crate::{easing::Easing, utils::{align_gif, align_speed, get_delay_centisecs}};
 
 #[derive(Copy, Clone)]
 struct WiggleData {
@@ -24,18 +24,8 @@ impl WiggleData {
         }
     }
 
-    fn set_shift(&mut self, cycle: f32, interval: f32) {
-        let shift_step = interval / 8.0;
-
-        let mut cur_shift_step = shift_step;
-        let mut step = 0;
-
-        while cycle >= cur_shift_step {
-            cur_shift_step += shift_step;
-            step += 1;
-        }
-
-        self.step = step;
+    fn set_shift(&mut self, progress: f32) {
+        self.step = ((progress * 8.0).floor() as i64).rem_euclid(8) as usize;
         self.set_shift_by_step(0);
     }
 
@@ -59,7 +49,7 @@ impl WiggleData {
     }
 }
 
-pub fn wiggle(frames: &mut Vec<Frame>, speed: f32) {
+pub fn wiggle(frames: &mut Vec<Frame>, speed: f32, easing: Easing) {
     align_speed(frames, 6.0);
     let Some(frame) = frames.first() else { return };
     let buffer_width = frame.buffer().width() as f32;
@@ -76,13 +66,14 @@ pub fn wiggle(frames: &mut Vec<Frame>, speed: f32) {
     *frames = align_gif(frames, interval.floor() as usize);
 
     for (index, frame) in frames.iter_mut().enumerate() {
+        let cycle = index as f32 % interval;
+
         wiggle_frame_data(
             frame,
             buffer_width as u32,
             buffer_height as u32,
             wiggle_data,
-            index as f32,
-            interval,
+            easing.apply(cycle / interval),
         );
     }
 }
@@ -92,8 +83,7 @@ fn wiggle_frame_data(
     width: u32,
     height: u32,
     mut wiggle_data: WiggleData,
-    frame_index: f32,
-    interval: f32,
+    progress: f32,
 ) {
     let mut wiggled_buffer = RgbaImage::new(width, height);
 
@@ -105,8 +95,7 @@ fn wiggle_frame_data(
                 return;
             }
 
-            let cycle = frame_index % interval;
-            wiggle_data.set_shift(cycle, interval);
+            wiggle_data.set_shift(progress);
             wiggle_data.set_shift_by_step(index);
 
             let cropped_buffer = frame
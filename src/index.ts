@@ -100,7 +100,7 @@ export default class EmoteReplacerPlugin implements Plugin {
     );
 
     this.gifProcessingService = new GifProcessingService(this);
-    await this.gifProcessingService.start();
+    await this.gifProcessingService.start(this.emoteService);
 
     this.sendMessageService = new SendMessageService(this);
     await this.sendMessageService.start(
@@ -1,4 +1,13 @@
 export interface Command {
   name: string
   param?: string
+  options?: string[]
+  data?: Uint8Array
+  text?: string
+}
+
+export interface ComposeInput {
+  url: string
+  formatType: string
+  options: string[][]
 }
@@ -1,6 +1,7 @@
 export enum WorkerMessageType {
   INIT,
-  APPLY_COMMANDS
+  APPLY_COMMANDS,
+  COMPOSE
 }
 
 export interface WorkerMessage {
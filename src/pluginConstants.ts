@@ -52,19 +52,25 @@ export const EMOTE_MODIFIERS: Modifier[] = [
   {
     name: 'speed',
     type: 'normal',
-    info: 'Delay between frames in hundredths of a second',
+    info: 'Delay between frames in hundredths of a second, or x<number> to speed up by a factor',
     arguments: ['number'],
   },
   {
     name: 'hyperspeed',
     type: 'normal',
-    info: 'Remove every other frame and use minimum frame delay',
+    info: 'Keep every Nth frame (default 2) and use minimum frame delay',
+    arguments: ['', 'number'],
   },
   {
     name: 'reverse',
     type: 'normal',
     info: 'Play animation backwards',
   },
+  {
+    name: 'boomerang',
+    type: 'normal',
+    info: 'Play animation forwards then backwards',
+  },
   {
     name: 'spin',
     type: 'gif',
@@ -80,14 +86,50 @@ export const EMOTE_MODIFIERS: Modifier[] = [
   {
     name: 'slide',
     type: 'gif',
-    info: 'Slide emote from right to left, options: empty, fast, faster, hyper',
-    arguments: ['', 'fast', 'faster', 'hyper'],
+    info: 'Slide emote from right to left, options: empty, fast, faster, hyper, out',
+    arguments: ['', 'fast', 'faster', 'hyper', 'out'],
   },
   {
     name: 'sliderev',
     type: 'gif',
-    info: 'Slide emote from left to right, options: empty, fast, faster, hyper',
-    arguments: ['', 'fast', 'faster', 'hyper'],
+    info: 'Slide emote from left to right, options: empty, fast, faster, hyper, out',
+    arguments: ['', 'fast', 'faster', 'hyper', 'out'],
+  },
+  {
+    name: 'slideup',
+    type: 'gif',
+    info: 'Slide emote upwards, options: empty, fast, faster, hyper, out',
+    arguments: ['', 'fast', 'faster', 'hyper', 'out'],
+  },
+  {
+    name: 'slidedown',
+    type: 'gif',
+    info: 'Slide emote downwards, options: empty, fast, faster, hyper, out',
+    arguments: ['', 'fast', 'faster', 'hyper', 'out'],
+  },
+  {
+    name: 'slidediag',
+    type: 'gif',
+    info: 'Slide emote diagonally from top left, options: empty, fast, faster, hyper, out',
+    arguments: ['', 'fast', 'faster', 'hyper', 'out'],
+  },
+  {
+    name: 'slidediagrev',
+    type: 'gif',
+    info: 'Slide emote diagonally from bottom right, options: empty, fast, faster, hyper, out',
+    arguments: ['', 'fast', 'faster', 'hyper', 'out'],
+  },
+  {
+    name: 'slideantidiag',
+    type: 'gif',
+    info: 'Slide emote diagonally from bottom left, options: empty, fast, faster, hyper, out',
+    arguments: ['', 'fast', 'faster', 'hyper', 'out'],
+  },
+  {
+    name: 'slideantidiagrev',
+    type: 'gif',
+    info: 'Slide emote diagonally from top right, options: empty, fast, faster, hyper, out',
+    arguments: ['', 'fast', 'faster', 'hyper', 'out'],
   },
   {
     name: 'shake',
@@ -104,8 +146,14 @@ export const EMOTE_MODIFIERS: Modifier[] = [
   {
     name: 'infinite',
     type: 'gif',
-    info: 'Pulse emote outwards, options: empty, fast, faster, hyper',
-    arguments: ['', 'fast', 'faster', 'hyper'],
+    info: 'Pulse emote outwards, options: empty, fast, faster, hyper, smooth, fade, layers<1-10>, spacing<10-200>',
+    arguments: ['', 'fast', 'faster', 'hyper', 'smooth', 'fade'],
+  },
+  {
+    name: 'infiniterev',
+    type: 'gif',
+    info: 'Zoom into emote forever, options: empty, fast, faster, hyper, smooth, fade, layers<1-10>, spacing<10-200>',
+    arguments: ['', 'fast', 'faster', 'hyper', 'smooth', 'fade'],
   },
   {
     name: 'wiggle',
@@ -113,6 +161,235 @@ export const EMOTE_MODIFIERS: Modifier[] = [
     info: 'Wiggle emote, options: empty, fast, faster, hyper',
     arguments: ['', 'fast', 'faster', 'hyper'],
   },
+  {
+    name: 'bounce',
+    type: 'gif',
+    info: 'Bounce emote up and down, options: empty, fast, faster, hyper, squash',
+    arguments: ['', 'fast', 'faster', 'hyper', 'squash'],
+  },
+  {
+    name: 'pulse',
+    type: 'gif',
+    info: 'Pulse emote size, options: empty, fast, faster, hyper, min<10-200>, max<10-200>',
+    arguments: ['', 'fast', 'faster', 'hyper'],
+  },
+  {
+    name: 'heartbeat',
+    type: 'gif',
+    info: 'Pulse emote size like a heartbeat, options: empty, fast, faster, hyper, min<10-200>, max<10-200>',
+    arguments: ['', 'fast', 'faster', 'hyper'],
+  },
+  {
+    name: 'rock',
+    type: 'gif',
+    info: 'Rock emote back and forth, options: empty, fast, faster, hyper, bottom, angle<1-90>',
+    arguments: ['', 'fast', 'faster', 'hyper', 'bottom'],
+  },
+  {
+    name: 'jello',
+    type: 'gif',
+    info: 'Squish and stretch emote like jello, options: empty, fast, faster, hyper',
+    arguments: ['', 'fast', 'faster', 'hyper'],
+  },
+  {
+    name: 'pet',
+    type: 'gif',
+    info: 'Pet emote, options: empty, fast, faster, hyper',
+    arguments: ['', 'fast', 'faster', 'hyper'],
+  },
+  {
+    name: 'fade',
+    type: 'gif',
+    info: 'Fade emote out and back in, options: empty, fast, faster, hyper',
+    arguments: ['', 'fast', 'faster', 'hyper'],
+  },
+  {
+    name: 'fadein',
+    type: 'gif',
+    info: 'Fade emote in once, options: empty, fast, faster, hyper',
+    arguments: ['', 'fast', 'faster', 'hyper'],
+  },
+  {
+    name: 'fadeout',
+    type: 'gif',
+    info: 'Fade emote out once, options: empty, fast, faster, hyper',
+    arguments: ['', 'fast', 'faster', 'hyper'],
+  },
+  {
+    name: 'blink',
+    type: 'gif',
+    info: 'Make emote blink, options: empty, fast, faster, hyper',
+    arguments: ['', 'fast', 'faster', 'hyper'],
+  },
+  {
+    name: 'grayscale',
+    type: 'gif',
+    info: 'Make emote grayscale',
+  },
+  {
+    name: 'sepia',
+    type: 'gif',
+    info: 'Give emote a sepia tone',
+  },
+  {
+    name: 'invert',
+    type: 'gif',
+    info: 'Invert emote colors',
+  },
+  {
+    name: 'tint',
+    type: 'gif',
+    info: 'Tint emote to a color, options: red, orange, yellow, green, cyan, blue, purple, pink, white, gray, black or a hex color',
+    arguments: ['red', 'orange', 'yellow', 'green', 'cyan', 'blue', 'purple', 'pink', 'white', 'gray', 'black'],
+  },
+  {
+    name: 'saturation',
+    type: 'gif',
+    info: 'Change emote saturation in percent',
+    arguments: ['number'],
+  },
+  {
+    name: 'brightness',
+    type: 'gif',
+    info: 'Change emote brightness in percent',
+    arguments: ['number'],
+  },
+  {
+    name: 'contrast',
+    type: 'gif',
+    info: 'Change emote contrast in percent',
+    arguments: ['number'],
+  },
+  {
+    name: 'hue',
+    type: 'gif',
+    info: 'Rotate emote hue by x degrees',
+    arguments: ['number'],
+  },
+  {
+    name: 'deepfry',
+    type: 'gif',
+    info: 'Deep fry emote, options: empty, extra, nuclear',
+    arguments: ['', 'extra', 'nuclear'],
+  },
+  {
+    name: 'pixelate',
+    type: 'gif',
+    info: 'Pixelate emote, options: a block size in pixels, censor or reveal to animate it and a speed',
+    arguments: ['', 'number', 'censor', 'reveal'],
+  },
+  {
+    name: 'blur',
+    type: 'gif',
+    info: 'Blur emote, options: a radius in pixels',
+    arguments: ['', 'number'],
+  },
+  {
+    name: 'sharpen',
+    type: 'gif',
+    info: 'Sharpen emote, options: a strength in percent',
+    arguments: ['', 'number'],
+  },
+  {
+    name: 'glitch',
+    type: 'gif',
+    info: 'Glitch emote, options: empty, fast, faster, hyper, intensity<1-3>, seed<number>',
+    arguments: ['', 'fast', 'faster', 'hyper'],
+  },
+  {
+    name: 'outline',
+    type: 'gif',
+    info: 'Outline emote, options: empty, a color, a size in pixels',
+    arguments: ['', 'white', 'black', 'red', 'yellow', 'blue'],
+  },
+  {
+    name: 'glow',
+    type: 'gif',
+    info: 'Make emote glow, options: empty, a color, a size in pixels',
+    arguments: ['', 'white', 'yellow', 'red', 'blue', 'pink'],
+  },
+  {
+    name: 'shadow',
+    type: 'gif',
+    info: 'Add a drop shadow to emote, options: empty, a color, a size in pixels',
+    arguments: ['', 'black', 'gray'],
+  },
+  {
+    name: 'crop',
+    type: 'gif',
+    info: 'Crop emote around the center in percent (e.g. 50 or 80x50), or a rectangle as left-top-width-height in percent',
+    arguments: ['number'],
+  },
+  {
+    name: 'pad',
+    type: 'gif',
+    info: 'Add a border in percent of the emote size, options: a number followed by an optional color',
+    arguments: ['number'],
+  },
+  {
+    name: 'trim',
+    type: 'gif',
+    info: 'Remove transparent edges',
+  },
+  {
+    name: 'square',
+    type: 'gif',
+    info: 'Pad emote to a square, options: empty or a color',
+    arguments: ['', 'white', 'black'],
+  },
+  {
+    name: 'bg',
+    type: 'gif',
+    info: 'Fill the background, options: a color, two colors for a gradient (add horizontal to go sideways) or checker with optional colors and size',
+    arguments: ['white', 'black', 'checker'],
+  },
+  {
+    name: 'chroma',
+    type: 'gif',
+    info: 'Remove a background color, options: a color (defaults to the most common edge color) and a tolerance from 0 - 99',
+    arguments: ['', 'white', 'green', 'black'],
+  },
+  {
+    name: 'removebg',
+    type: 'gif',
+    info: 'Remove the background connected to the edges, options: a tolerance from 0 - 99',
+    arguments: ['', 'number'],
+  },
+  {
+    name: 'mask',
+    type: 'gif',
+    info: 'Cut emote into a shape, options: circle, rounded, heart, star or the name of an emote to use as the mask',
+    arguments: ['circle', 'rounded', 'heart', 'star'],
+  },
+  {
+    name: 'sticker',
+    type: 'gif',
+    info: 'Add an animated sticker, options: bubble, sweat, anger, hearts, sparkles, question or sunglasses, followed by an optional position, size in percent and speed',
+    arguments: ['bubble', 'sweat', 'anger', 'hearts', 'sparkles', 'question', 'sunglasses'],
+  },
+  {
+    name: 'combo',
+    type: 'gif',
+    info: 'Put another emote next to this one, options: the name of the emote followed by an optional layout (horizontal, vertical, grid or overlay)',
+    arguments: ['emote'],
+  },
+  {
+    name: 'trail',
+    type: 'gif',
+    info: 'Leave a trail of fading copies behind moving effects, options: the amount of copies (1 - 8)',
+    arguments: ['', 'number'],
+  },
+  {
+    name: 'text',
+    type: 'gif',
+    info: 'Add a caption, words are separated by underscores, options: top, bottom, center, typewriter, marquee, a color and a speed',
+    arguments: ['top', 'bottom', 'center', 'typewriter', 'marquee'],
+  },
+  {
+    name: 'meme',
+    type: 'gif',
+    info: 'Add meme text to the top and bottom, words are separated by underscores',
+  },
   {
     name: 'wide',
     type: 'normal',
@@ -128,7 +405,37 @@ export const EMOTE_MODIFIERS: Modifier[] = [
   {
     name: 'rain',
     type: 'gif',
-    info: 'Add rain, options: empty, glitter',
+    info: 'Add rain, options: empty, glitter, an emitter (top, bottom, burst) and a speed',
     arguments: ['', 'glitter'],
   },
+  {
+    name: 'confetti',
+    type: 'gif',
+    info: 'Add falling confetti, options: an emitter (top, bottom, burst) and a speed',
+    arguments: ['', 'burst'],
+  },
+  {
+    name: 'hearts',
+    type: 'gif',
+    info: 'Add rising hearts, options: an emitter (top, bottom, burst) and a speed',
+    arguments: ['', 'burst'],
+  },
+  {
+    name: 'sparkle',
+    type: 'gif',
+    info: 'Add twinkling sparkles, options: a speed',
+    arguments: ['', 'fast', 'faster', 'hyper'],
+  },
+  {
+    name: 'fire',
+    type: 'gif',
+    info: 'Set emote on fire, options: a speed',
+    arguments: ['', 'fast', 'faster', 'hyper'],
+  },
+  {
+    name: 'bubbles',
+    type: 'gif',
+    info: 'Add rising bubbles, options: an emitter (top, bottom, burst) and a speed',
+    arguments: ['', 'burst'],
+  },
 ];
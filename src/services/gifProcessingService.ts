@@ -1,14 +1,21 @@
 import { BaseService } from './baseService';
-import { Command } from '../interfaces/gifData';
+import { EmoteService } from './emoteService';
+import { Command, ComposeInput } from '../interfaces/gifData';
 import Worker from 'web-worker:../worker.ts';
 import { GifWorker, WorkerMessage, WorkerMessageType } from '../interfaces/workerData';
 import { Utils } from '../utils/utils';
 
+const SPEED_OPTIONS = ['fast', 'faster', 'hyper'];
+const MASK_SHAPES = ['circle', 'rounded', 'heart', 'star'];
+const COMPOSE_LAYOUTS = ['horizontal', 'vertical', 'grid', 'overlay'];
+
 export class GifProcessingService extends BaseService {
+  emoteService!: EmoteService;
   public isProcessing = false;
   private worker?: GifWorker;
 
-  public async start(): Promise<void> {
+  public async start(emoteService: EmoteService): Promise<void> {
+    this.emoteService = emoteService;
     await this.getWorker();
   }
 
@@ -63,7 +70,20 @@ export class GifProcessingService extends BaseService {
     options: string[][]
   ): Promise<Uint8Array> {
     this.logger.info('Got GIF request', url, options);
+
+    // Combos put other emotes next to this one, its other modifiers only apply to itself
+    const combos = options.filter((option) => option[0] === 'combo');
+    if (combos.length > 0) {
+      const inputs: ComposeInput[] = [
+        { url, formatType, options: options.filter((option) => option[0] !== 'combo') },
+        ...combos.map((combo) => this.getComboInput(combo[1])),
+      ];
+
+      return this.composeGifsImpl(inputs, this.getComposeLayout(combos[0]?.slice(2) ?? []));
+    }
+
     const commands = this.getCommands(options);
+    await this.loadMaskImages(commands);
     this.logger.info('Processed request commands', commands);
 
     const result = await this.processCommands(url, formatType, commands);
@@ -72,6 +92,36 @@ export class GifProcessingService extends BaseService {
     return result;
   }
 
+  private async composeGifsImpl(inputs: ComposeInput[], layout: Command): Promise<Uint8Array> {
+    this.logger.info('Got compose request', inputs, layout);
+
+    // Every emote keeps its own modifiers, the layout decides how they are put together
+    const requestInputs = await Promise.all(
+      inputs.map(async (input) => {
+        const commands = this.getCommands(input.options);
+        await this.loadMaskImages(commands);
+
+        return {
+          data: await Utils.urlGetBuffer(input.url),
+          formatType: input.formatType,
+          commands,
+        };
+      })
+    );
+
+    const worker = await this.getWorker();
+    const request: WorkerMessage = {
+      type: WorkerMessageType.COMPOSE,
+      data: { inputs: requestInputs, layout },
+    };
+
+    const response = await Utils.workerMessagePromise(worker, request);
+    if (!(response instanceof Uint8Array)) throw Error('Did not compose gif!');
+
+    this.logger.info('Composed emotes', { length: response.length });
+    return response;
+  }
+
   private getCommands(options: string[][]): Command[] {
     const commands: Command[] = [];
 
@@ -86,7 +136,8 @@ export class GifProcessingService extends BaseService {
           commands.push(command);
           break;
         }
-        case 'reverse': {
+        case 'reverse':
+        case 'boomerang': {
           commands.push({ name: option[0] });
           break;
         }
@@ -99,42 +150,170 @@ export class GifProcessingService extends BaseService {
         case 'speed': {
           const param = option[1]?.toString() ?? '';
 
+          if (param.startsWith('x')) {
+            commands.push({
+              name: 'speedmultiply',
+              param: Math.max(0.1, parseFloat(param.substring(1)) || 1).toString(),
+            });
+            break;
+          }
+
           commands.push({
             name: option[0],
             param: Math.max(2, parseFloat(param)).toString(),
           });
           break;
         }
-        case 'hyperspeed':
-          commands.push({ name: 'hyperspeed' });
+        case 'hyperspeed': {
+          const param = option[1]?.toString() ?? '';
+
+          commands.push({
+            name: option[0],
+            param: Math.max(2, parseInt(param) || 2).toString(),
+          });
           break;
+        }
         case 'rotate':
           commands.push({ name: option[0], param: option[1] });
           break;
-        case 'rain':
+        case 'grayscale':
+        case 'sepia':
+        case 'invert':
+          commands.push({ name: option[0] });
+          break;
+        case 'tint':
+          commands.push({ name: option[0], options: option.slice(1) });
+          break;
+        case 'saturation':
+        case 'brightness':
+        case 'contrast':
+          commands.push({ name: option[0], param: option[1] || '100' });
+          break;
+        case 'hue':
+          commands.push({ name: option[0], param: option[1] || '0' });
+          break;
+        case 'blur':
+          commands.push({ name: option[0], param: option[1] || '4' });
+          break;
+        case 'sharpen':
+          commands.push({ name: option[0], param: option[1] || '100' });
+          break;
+        case 'deepfry': {
+          let intensity = '1';
+          const param = option[1];
+
+          if (param === 'extra') intensity = '2';
+          else if (param === 'nuclear') intensity = '3';
+
+          commands.push({ name: option[0], param: intensity });
+          break;
+        }
+        case 'outline':
+        case 'glow':
+        case 'shadow': {
+          // Colors can be written in hex, so only short numbers are sizes
+          const args = option.slice(1);
+          const size = args.find((arg) => /^\d{1,2}$/.test(arg));
+
+          commands.push({
+            name: option[0],
+            param: size ?? '0',
+            options: args.filter((arg) => arg !== '' && arg !== size),
+          });
+          break;
+        }
+        case 'crop': {
+          const args = option.slice(1).filter((arg) => arg !== '');
+
+          // Four values crop a rectangle, otherwise crop around the center
+          if (args.length >= 4) {
+            commands.push({ name: option[0], param: '0', options: args.slice(0, 4) });
+          } else {
+            commands.push({ name: option[0], param: args[0] ?? '50' });
+          }
+          break;
+        }
+        case 'pad':
+          commands.push({
+            name: option[0],
+            param: option[1] || '10',
+            options: option.slice(2),
+          });
+          break;
+        case 'trim':
+          commands.push({ name: option[0] });
+          break;
+        case 'square':
+          commands.push({ name: option[0], options: option.slice(1) });
+          break;
+        case 'chroma':
+        case 'removebg': {
+          // Colors can be written in hex, so only short numbers are tolerances
+          const args = option.slice(1);
+          const tolerance = args.find((arg) => /^\d{1,2}$/.test(arg));
+
           commands.push({
             name: option[0],
-            param: option[1] === 'glitter' ? '1' : '0',
+            param: tolerance ?? (option[0] === 'chroma' ? '20' : '10'),
+            options: args.filter((arg) => arg !== '' && arg !== tolerance),
           });
           break;
+        }
+        case 'bg':
+          commands.push({ name: option[0], options: option.slice(1) });
+          break;
+        case 'text': {
+          // Spaces would end the command, so words are joined with underscores
+          const speedCommand = this.getSpeedCommand([option[0], ...option.slice(2)]);
+          commands.push({ ...speedCommand, text: this.getText(option[1]) });
+          break;
+        }
+        case 'meme':
+          if (option[1]) commands.push({ name: 'text', options: ['top'], text: this.getText(option[1]) });
+          if (option[2]) commands.push({ name: 'text', options: ['bottom'], text: this.getText(option[2]) });
+          break;
+        case 'mask':
+          commands.push({ name: option[0], options: option.slice(1) });
+          break;
+        case 'trail':
+          commands.push({ name: option[0], param: option[1] || '3' });
+          break;
         case 'spin':
         case 'spinrev':
         case 'shake':
         case 'rainbow':
+        case 'wiggle':
         case 'infinite':
+        case 'infiniterev':
         case 'slide':
         case 'sliderev':
-        case 'wiggle': {
-          let speed = '8';
-          const param = option[1];
-
-          if (param === 'fast') speed = '6';
-          else if (param === 'faster') speed = '4';
-          else if (param === 'hyper') speed = '2';
-
-          commands.push({ name: option[0], param: speed });
+        case 'slideup':
+        case 'slidedown':
+        case 'slidediag':
+        case 'slidediagrev':
+        case 'slideantidiag':
+        case 'slideantidiagrev':
+        case 'bounce':
+        case 'pulse':
+        case 'heartbeat':
+        case 'rock':
+        case 'jello':
+        case 'pet':
+        case 'fade':
+        case 'fadein':
+        case 'fadeout':
+        case 'blink':
+        case 'glitch':
+        case 'pixelate':
+        case 'sticker':
+        case 'rain':
+        case 'confetti':
+        case 'hearts':
+        case 'sparkle':
+        case 'fire':
+        case 'bubbles':
+          commands.push(this.getSpeedCommand(option));
           break;
-        }
         default:
           break;
       }
@@ -143,6 +322,65 @@ export class GifProcessingService extends BaseService {
     return commands;
   }
 
+  private getSpeedCommand(option: string[]): Command {
+    const args = option.slice(1);
+    const speed = args.find((arg) => SPEED_OPTIONS.includes(arg));
+
+    return {
+      name: option[0] ?? '',
+      param: this.getSpeed(speed),
+      options: args.filter((arg) => arg !== '' && arg !== speed),
+    };
+  }
+
+  private getComboInput(name: string | undefined): ComposeInput {
+    const emoteNames = this.emoteService.emoteNames ?? {};
+    const url = emoteNames[this.emoteService.getPrefixedName(name ?? '')];
+    if (url === undefined) throw new Error(`Unknown combo emote: ${name ?? ''}`);
+
+    return { url, formatType: url.endsWith('.gif') ? 'gif' : 'png', options: [] };
+  }
+
+  private getComposeLayout(args: string[]): Command {
+    const layout = args.find((arg) => COMPOSE_LAYOUTS.includes(arg)) ?? 'horizontal';
+    const size = args.find((arg) => /^\d+$/.test(arg));
+
+    return {
+      name: layout,
+      param: size ?? '0',
+      options: args.filter((arg) => arg !== '' && arg !== layout && arg !== size),
+    };
+  }
+
+  private getText(param: string | undefined): string {
+    return (param ?? '').replace(/_/g, ' ');
+  }
+
+  private getSpeed(param: string | undefined): string {
+    if (param === 'fast') return '6';
+    if (param === 'faster') return '4';
+    if (param === 'hyper') return '2';
+
+    return '8';
+  }
+
+  private async loadMaskImages(commands: Command[]): Promise<void> {
+    for (const command of commands) {
+      if (command.name !== 'mask') continue;
+
+      // Anything that isn't a shape is the name of an emote to use as the mask
+      const name = command.options?.[0] ?? '';
+      if (name === '' || MASK_SHAPES.includes(name)) continue;
+
+      const emoteNames = this.emoteService.emoteNames ?? {};
+      const url = emoteNames[this.emoteService.getPrefixedName(name)];
+      if (url === undefined) throw new Error(`Unknown mask: ${name}`);
+
+      command.data = await Utils.urlGetBuffer(url);
+      command.options = [];
+    }
+  }
+
   private async processCommands(
     url: string,
     formatType: string,
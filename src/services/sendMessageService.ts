@@ -10,6 +10,13 @@ import { GifProcessingService } from './gifProcessingService';
 import { Utils } from '../utils/utils';
 import { CloseNotice } from 'betterdiscord';
 import { Attachment } from '../interfaces/attachment';
+import { EMOTE_MODIFIERS } from '../pluginConstants';
+
+// Anything shorter than 4 characters after a dot is usually a file extension (.com, .png) rather than a modifier,
+// so short modifiers are only recognised by name, and only those that work without an argument
+const SHORT_MODIFIERS = EMOTE_MODIFIERS.filter(
+  (modifier) => modifier.name.length < 4 && modifier.arguments?.includes('')
+).map((modifier) => modifier.name);
 
 export class SendMessageService extends BaseService {
   emoteService!: EmoteService;
@@ -225,7 +232,9 @@ export class SendMessageService extends BaseService {
 
     Object.entries(emoteCandidates).forEach(([key, value]) => {
       const regex = new RegExp('(?<!\\/)' + key + '(?<=\\b|>)', 'g');
-      const regexCommand = new RegExp(key + '(\\.\\S{4,}\\b)+');
+      const regexCommand = new RegExp(
+        key + '(\\.(?:\\S{4,}|' + SHORT_MODIFIERS.join('|') + ')\\b)+'
+      );
       const matches = content.match(regex);
       const command = content.match(regexCommand);
 
@@ -246,13 +255,14 @@ export class SendMessageService extends BaseService {
         };
 
         if (command) {
-          const commands = command[0]?.split('.') ?? [];
+          // Dots followed by a digit are decimals, e.g. speed-x0.5
+          const commands = command[0]?.split(/\.(?!\d)/) ?? [];
           emote.commands = commands
             .filter((command) => command !== key)
             .map((command) => {
               const split = command.split('-');
 
-              return [split[0] ?? '', split[1] ?? ''];
+              return [split[0] ?? '', split[1] ?? '', ...split.slice(2)];
             });
 
           emote.nameAndCommand = command[0] ?? '';
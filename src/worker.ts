@@ -1,5 +1,5 @@
 import { WorkerMessage, WorkerMessageType } from './interfaces/workerData'
-import init, { applyCommands, initPanicHook } from '../rust/pkg/gif_wasm'
+import init, { applyCommands, composeEmotes, initPanicHook } from '../rust/pkg/gif_wasm'
 import gifWasm from '../rust/pkg/gif_wasm_bg.wasm'
 import { Command } from './interfaces/gifData'
 
@@ -15,6 +15,9 @@ onmessage = (message) => {
     case WorkerMessageType.APPLY_COMMANDS:
       promise = doApplyCommands(request)
       break
+    case WorkerMessageType.COMPOSE:
+      promise = doCompose(request)
+      break
     default:
       promise = Promise.reject(new Error('Unknown request type'))
       break
@@ -57,3 +60,16 @@ async function doApplyCommands (message: WorkerMessage): Promise<Uint8Array> {
   const result = applyCommands(data, formatType, commands)
   return await Promise.resolve(result)
 }
+
+async function doCompose (message: WorkerMessage): Promise<Uint8Array> {
+  const {
+    inputs,
+    layout
+  } = message.data as {
+    inputs: Array<{ data: Uint8Array, formatType: string, commands: Command[] }>,
+    layout: Command
+  }
+
+  const result = composeEmotes(inputs, layout)
+  return await Promise.resolve(result)
+}